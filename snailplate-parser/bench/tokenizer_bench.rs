@@ -0,0 +1,90 @@
+// Tokenizer throughput on large templates.
+//
+// Each template from bench/fixture is repeated till it is at least
+// CORPUS_LEN_MIN bytes large and tokenized ITERATIONS times, best time is
+// reported. Templates differ in what Tokenizer spends time on: plain text,
+// tags with attributes, or instruction arguments.
+//
+// Run with:
+// cargo bench --bench tokenizer_bench
+//
+// Compare with scalar scanning, where each byte is looked up one by one:
+// cargo bench --bench tokenizer_bench -F scalar_tokenizer_scan
+use std::{
+   fs,
+   path::Path,
+   time::{Duration, Instant},
+};
+
+use snailplate_parser::tokenizer::Tokenizer;
+
+
+
+const CORPUS_LEN_MIN: usize = 16 * 1024 * 1024;
+const ITERATIONS: usize = 10;
+
+const FIXTURES: [&str; 4] = [
+   "article.html",
+   "minified.html",
+   "form.html",
+   "instruction.html",
+];
+
+
+
+fn corpus_load(name: &str) -> String {
+   let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("bench/fixture").join(name);
+   let template = match fs::read_to_string(&path) {
+      Ok(template) => template,
+      Err(e) => panic!("Could not read fixture {:?}: {}", path, e),
+   };
+
+   let repeat = CORPUS_LEN_MIN.div_ceil(template.len());
+   template.repeat(repeat)
+}
+
+
+
+// Tokenize corpus and return the number of tokens and time spent. Corpus is
+// borrowed, thus it is not copied for each iteration.
+fn corpus_tokenize(corpus: &str) -> (usize, Duration) {
+   let mut t = Tokenizer::new();
+
+   if let Err(token) = t.src_push_borrowed(None, corpus.as_bytes()) {
+      panic!("Could not push corpus: {:?}", token);
+   }
+
+   let start = Instant::now();
+   let cnt_token = (&mut t).count();
+   (cnt_token, start.elapsed())
+}
+
+
+
+fn main() {
+   let scan = match cfg!(feature = "scalar_tokenizer_scan") {
+      true => "scalar",
+      false => "default",
+   };
+
+   println!("Tokenizer throughput, {} scanning, best of {} iterations", scan,
+      ITERATIONS
+   );
+
+   for name in FIXTURES {
+      let corpus = corpus_load(name);
+
+      let mut cnt_token = 0;
+      let mut best = Duration::MAX;
+      for _ in 0..ITERATIONS {
+         let (cnt, elapsed) = corpus_tokenize(&corpus);
+         cnt_token = cnt;
+         best = best.min(elapsed);
+      }
+
+      let mb = corpus.len() as f64 / (1024.0 * 1024.0);
+      println!("{:<20} {:>6.1} MB {:>10} tokens {:>9.2} ms {:>8.1} MB/s", name,
+         mb, cnt_token, best.as_secs_f64() * 1000.0, mb / best.as_secs_f64()
+      );
+   }
+}
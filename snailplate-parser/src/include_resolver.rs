@@ -9,10 +9,7 @@ use std::{
 
 use crate::{
    token::Token,
-   tokenizer::{
-      Tokenizer,
-      TokenizerState,
-   },
+   tokenizer::{Tokenizer, Syntax},
    tokenbody::TokenBody,
    tokenbuf::TokenBuf,
    span::Span,
@@ -40,7 +37,8 @@ enum IncludeResolverState {
    ResolveInclude,
 
    // Due to bugs in sub-components resolver can go into Failed state. This
-   // should be rare.
+   // should be rare. Resolver fails as well, when error or warning limit is
+   // exceeded, see error_limit_set.
    Failed,
 }
 
@@ -61,24 +59,27 @@ enum IncludeResolverSubState {
 // When receiving Tokens for complete batch, in each iteration it is necessary
 // to decide if given batch is valid or Tokenizer returned a Token, that breaks
 // whole chain thus batch becomes invalid.
+//
+// Tokens are only peeked, they stay in Tokenizer till batch is finalized or
+// failed.
 enum IncludeResult {
    // This token is valid at this point in time, thus batch assembly must
    // continue.
-   Progress(Token),
+   Progress,
 
    // This token is valid, but it finalizes given batch. Batch handling routines
    // must be run.
-   Finalized(Token),
+   Finalized,
 
    // This token is not valid, and it breaks the batch. IncludeResolver should
-   // pass-through tokens as is with added warning/errors Tokens if necessary.
-   Failed(Token),
+   // pass-through tokens as is with added warning/errors Token if given.
+   Failed(Option<Token>),
 }
 
 
 
 pub struct IncludeResolver {
-   pub tokenizer: Tokenizer,
+   pub tokenizer: Tokenizer<'static>,
 
    state: IncludeResolverState,
    substate: IncludeResolverSubState,
@@ -86,20 +87,34 @@ pub struct IncludeResolver {
    // This buffer stores Tokens temporarily. The idea is that while Resolver is
    // consuming Tokens, it can happen that it must return multiple tokens when
    // only single Token is received.
+   //
+   // While in ResolveInclude state, it holds only @include; Iterator does not
+   // return buffered Tokens in that state. The rest of batch is peeked and is
+   // taken from Tokenizer when batch is complete.
    tokenbuf: TokenBuf,
 
-   // Since IncludeResolver has to handle Tokens in batches, it must have a
-   // temporary buffer for current batch. Not to allocate it on each iteration
-   // have it here. It should be filled/emptied in single function call.
-   batchbuf: TokenBuf,
+   // Number of batch Tokens peeked from Tokenizer.
+   cnt_peeked: usize,
 
    // This can contain Span for include/require file path.
    tokenspan_file: Option<Span>,
 
+   // File path to be included, when batch is taken from Tokenizer.
+   include_file: Option<String>,
+
    // Path to directory where all template files should be searched for.
    root_dir: Option<String>,
 
-   include_pos_zero: Option<usize>
+   include_pos_zero: Option<usize>,
+
+   // Number of Error and Warning Tokens, that Iterator is allowed to return.
+   // These limits include Tokens from Tokenizer.
+   error_limit: usize,
+   warning_limit: usize,
+
+   // Number of Error and Warning Tokens returned by Iterator so far.
+   cnt_error: usize,
+   cnt_warning: usize,
 }
 
 
@@ -111,15 +126,30 @@ impl IncludeResolver {
          substate: IncludeResolverSubState::Uninitialized,
          tokenizer: Tokenizer::new(),
          tokenbuf: TokenBuf::new(),
-         batchbuf: TokenBuf::new(),
+         cnt_peeked: 0,
          tokenspan_file: None,
+         include_file: None,
          root_dir: None,
          include_pos_zero: None,
+         error_limit: usize::MAX,
+         warning_limit: usize::MAX,
+         cnt_error: 0,
+         cnt_warning: 0,
       }
    }
 
 
 
+   /// Create IncludeResolver, whose Tokenizer uses given instruction sigil and
+   /// interpolation delimiters, see Syntax::custom.
+   pub fn with_syntax(syntax: Syntax) -> Self {
+      let mut resolver = Self::new();
+      resolver.tokenizer = Tokenizer::with_syntax(syntax);
+      resolver
+   }
+
+
+
    pub fn template_root_dir_set(&mut self, root_dir: &str) {
       // TODO: here we should check if provided directory path is absolute
       // or relative. If path is relative, get current working directory and
@@ -139,6 +169,43 @@ impl IncludeResolver {
 
 
 
+   /// Set how many Error Tokens Iterator is allowed to return, including those
+   /// from Tokenizer. When one more Error is received, Fatal ErrorLimit is
+   /// returned instead and IncludeResolver fails. Default is usize::MAX, that
+   /// is no limit.
+   pub fn error_limit_set(&mut self, error_limit: usize) {
+      self.error_limit = error_limit;
+   }
+
+
+
+   /// Set how many Warning Tokens Iterator is allowed to return, see
+   /// error_limit_set. Default is usize::MAX, that is no limit.
+   pub fn warning_limit_set(&mut self, warning_limit: usize) {
+      self.warning_limit = warning_limit;
+   }
+
+
+
+   /// Reset IncludeResolver and its Tokenizer, so that they can be reused for
+   /// the next template, see Tokenizer::reset. Buffered Tokens are dropped,
+   /// allocated capacity is kept. Template root directory and limits are kept
+   /// as well.
+   pub fn reset(&mut self) {
+      self.tokenizer.reset();
+      self.state = IncludeResolverState::Passthrough;
+      self.substate = IncludeResolverSubState::Uninitialized;
+      self.tokenbuf.clear();
+      self.cnt_peeked = 0;
+      self.tokenspan_file = None;
+      self.include_file = None;
+      self.include_pos_zero = None;
+      self.cnt_error = 0;
+      self.cnt_warning = 0;
+   }
+
+
+
    pub fn file_read(&mut self, filename: &str) -> Result<(), Token> {
       let root_dir = match &self.root_dir {
          None => panic!("must have root dir"),
@@ -198,7 +265,7 @@ impl IncludeResolver {
                self.include_pos_zero = Some(span.pos_zero);
 
                // Resolver takes care of @include token
-               if let Err(etoken) = self.batchbuf.append(Token::Phantom(op)) {
+               if let Err(etoken) = self.tokenbuf.push_back(Token::Phantom(op)) {
                   self.state = IncludeResolverState::Failed;
                   return Some(etoken);
                }
@@ -227,95 +294,113 @@ impl IncludeResolver {
 
 
 
+   // Peek the next Token of @include batch. Batch Tokens stay in Tokenizer's
+   // lookahead till it is known, if they are returned as Phantom or as-is,
+   // see DD-2026-10-16-08.
+   //
+   // # Return
+   //
+   // * 'Ok(None)' - Token stream ends, or streamed region needs more input.
+   //
+   // * 'Err(Token)' - Tokenizer could not keep peeked Token.
+   #[inline(always)]
+   fn batch_peek(&mut self) -> Result<Option<Token>, Token> {
+      match self.tokenizer.peek(self.cnt_peeked) {
+         Ok(Some(token)) => {
+            self.cnt_peeked += 1;
+            Ok(Some(token.clone()))
+         }
+         Ok(None) => Ok(None),
+         Err(etoken) => Err(etoken),
+      }
+   }
+
+
+
    // Handle expected Token::OpenParen when in ResolveInclude state.
    //
    // This function exists just to split code in more manageable/readable
    // chunks.
    #[inline(always)]
    fn next_resolve_include_expect_open_paren(&mut self) -> IncludeResult {
-      let t = &mut self.tokenizer;
-
       use Token as T;
       use TokenBody as Tb;
       use IncludeResolverSubState as SS;
 
       // This construct is used, so that we have less deep indentation.
-      let token = if let Some(token) = t.next() { token }
-      else {
-         // @include instruction requires arguments, thus parenthesis must
-         // exist, so we inform user about this.
-
-         let tok = IncludeResult::Failed(Token::Error(
-            ParseError::InstructionNotOpen(Source {
-               pos_zero: self.include_pos_zero.unwrap_or(0),
-               component: Component::IncludeResolver,
-               line: line!(),
-               code: 1,
-            })
-         ));
-
-         return tok;
+      let token = match self.batch_peek() {
+         Ok(Some(token)) => token,
+         Ok(None) => {
+            // @include instruction requires arguments, thus parenthesis must
+            // exist, so we inform user about this.
+
+            let tok = IncludeResult::Failed(Some(Token::Error(
+               ParseError::InstructionNotOpen(Source {
+                  pos_zero: self.include_pos_zero.unwrap_or(0),
+                  component: Component::IncludeResolver,
+                  line: line!(),
+                  code: 1,
+               })
+            )));
+
+            return tok;
+         }
+         Err(etoken) => return IncludeResult::Failed(Some(etoken)),
       };
 
       match token {
          T::Real(body) => match body {
-            tok @ Tb::OpenParen(..) => {
+            Tb::OpenParen(..) => {
                // TODO: here we could change Tokenizer state to "parse-path" or
                // something similar. At the moment we accept Defered token as
                // include file path.
 
                self.substate = SS::ExpectPath;
 
-               // Outer code must still know that @include/require token was
-               // tokenized, thus Token becomes a Phantom.
-               IncludeResult::Progress(T::Real(tok))
+               IncludeResult::Progress
             }
 
-            tok @ Tb::WhiteSpace(..) => {
+            Tb::WhiteSpace(..) => {
                // Based on DD-2023-07-14-01, IncludeResolver has transform
                // Real WhiteSpace to Phantom WhiteSpace when expecting
                // OpenParen.
                //
                // IncludeResolver does not have to emit any warning token,
                // because in this case it is Tokenizer's responsibility.
-               IncludeResult::Progress(T::Real(tok))
+               IncludeResult::Progress
             }
 
-            tok => {
+            _ => {
                // TODO: here we should actually return some error and warning
                // tokens as well. For now this would require to make an artificial
                // test/situation, since Tokenizer will not return @include
                // as a token if there is no open parenthesis (at the moment).
 
-               // Since this was unexpected token, Resolver switches back to
-               // pass-through state. @include instruction could not be
-               // satisfied.
-               self.state = IncludeResolverState::Passthrough;
-               self.substate = IncludeResolverSubState::Uninitialized;
-
-               IncludeResult::Failed(T::Real(tok))
+               // Since this was unexpected token, @include instruction could
+               // not be satisfied.
+               IncludeResult::Failed(None)
             }
          }
 
          // At the moment IncludeResolver ignores any Phantom token.
-         tok @ T::Phantom(..) => {IncludeResult::Progress(tok) }
+         T::Phantom(..) => { IncludeResult::Progress }
 
          // StateChange is silently passed through, because it should not
          // influence IncludeResolver.
-         tok @ T::StateChange => { IncludeResult::Progress(tok) }
+         T::StateChange => { IncludeResult::Progress }
 
          // If Tokenizer has returned Fatal, even if next() is called again,
          // it would return Fatal. Thus Resolver is allowed to be transparent.
          // But we are sure that instruction batch will not be collected.
-         tok @ T::Fatal(..) => { IncludeResult::Failed(tok) }
+         T::Fatal(..) => { IncludeResult::Failed(None) }
 
          // TODO: There are some errors that can be useful for Resolver as well,
          // i.e. Tokenizer informs that instruction was not started correctly.
          // At the moment i will leave this for future to be fixed.
          // There should be no errors between instruction and parenthesis.
-         tok @ T::Error(..) => { IncludeResult::Failed(tok) }
+         T::Error(..) => { IncludeResult::Failed(None) }
 
-         tok @ T::Warning(..) => { IncludeResult::Progress(tok) }
+         T::Warning(..) => { IncludeResult::Progress }
       }
    }
 
@@ -328,27 +413,28 @@ impl IncludeResolver {
    // file path to be included/required and cache it.
    #[inline(always)]
    fn next_resolve_include_expect_path(&mut self) -> IncludeResult {
-      let t = &mut self.tokenizer;
-
       use Token as T;
       use TokenBody as Tb;
       use IncludeResolverSubState as SS;
 
       // This construct is used, so that we have less deep indentation.
-      let token = if let Some(token) = t.next() { token }
-      else {
-         // There has to be path available within parenthesis.
-
-         let tok = IncludeResult::Failed(Token::Error(
-            ParseError::InstructionMissingArgs(Source {
-               pos_zero: self.include_pos_zero.unwrap_or(0),
-               component: Component::IncludeResolver,
-               line: line!(),
-               code: 2,
-            })
-         ));
-
-         return tok;
+      let token = match self.batch_peek() {
+         Ok(Some(token)) => token,
+         Ok(None) => {
+            // There has to be path available within parenthesis.
+
+            let tok = IncludeResult::Failed(Some(Token::Error(
+               ParseError::InstructionMissingArgs(Source {
+                  pos_zero: self.include_pos_zero.unwrap_or(0),
+                  component: Component::IncludeResolver,
+                  line: line!(),
+                  code: 2,
+               })
+            )));
+
+            return tok;
+         }
+         Err(etoken) => return IncludeResult::Failed(Some(etoken)),
       };
 
       match token {
@@ -356,7 +442,7 @@ impl IncludeResolver {
             // TODO: in future we should use special Token that describes
             // include path, instead of Defered. At the moment i do not want
             // to change Tokenizer's code.
-            tbody @ Tb::Defered(span) => {
+            Tb::Defered(span) => {
                self.substate = SS::ExpectCloseParen;
                self.tokenspan_file = Some(span);
 
@@ -365,30 +451,73 @@ impl IncludeResolver {
                // is valid file path, if is, then warn user about forgotten
                // close parenthesis.
 
-               return IncludeResult::Progress(T::Real(tbody));
+               IncludeResult::Progress
             }
-            _ => {
-               panic!("not impl")
+
+            // Quoted path, i.e. @include("file.html"). Quotes are not part
+            // of the file path. Backslash escapes are not resolved, since they
+            // are not expected in file paths. If string literal is not
+            // closed, Tokenizer follows it with an Error, which fails the
+            // include.
+            Tb::StringLiteral(span) => {
+               self.substate = SS::ExpectCloseParen;
+               self.tokenspan_file = Some(Span {
+                  index: span.index,
+                  pos_region: span.pos_region + 1,
+                  pos_zero: span.pos_zero + 1,
+                  pos_line: span.pos_line + 1,
+                  line: span.line,
+                  length: span.length.saturating_sub(2),
+               });
+
+               IncludeResult::Progress
+            }
+
+            tbody => {
+               // Path was not found where expected, i.e. "@include()" or
+               // "@include(\n  file.html)". Unexpected token is returned
+               // as-is together with the batch, and error Token follows it,
+               // as in next_resolve_include_failed. Error for ')' tells that
+               // there are no arguments, otherwise it points to the token,
+               // that was found instead of path.
+               let error = if let Tb::CloseParen(..) = tbody {
+                  ParseError::InstructionMissingArgs(Source {
+                     pos_zero: self.include_pos_zero.unwrap_or(0),
+                     component: Component::IncludeResolver,
+                     line: line!(),
+                     code: 9,
+                  })
+               }
+               else {
+                  ParseError::InstructionError(Source {
+                     pos_zero: tbody.span_clone().pos_zero,
+                     component: Component::IncludeResolver,
+                     line: line!(),
+                     code: 10,
+                  })
+               };
+
+               IncludeResult::Failed(Some(Token::Error(error)))
             }
          }
 
          // At the moment IncludeResolver ignores any Phantom token.
-         tok @ T::Phantom(..) => { IncludeResult::Progress(tok) }
+         T::Phantom(..) => { IncludeResult::Progress }
 
          // StateChange is silently passed through, because it should not
          // influence IncludeResolver while it is collecting include path.
-         tok @ T::StateChange => { IncludeResult::Progress(tok) }
+         T::StateChange => { IncludeResult::Progress }
 
          // If Tokenizer has returned Fatal, even if next() is called again,
          // it would return Fatal. Thus Resolver is allowed to be transparent.
-         tok @ T::Fatal(..) => { IncludeResult::Failed(tok) }
+         T::Fatal(..) => { IncludeResult::Failed(None) }
 
          // TODO: There are some errors that can be useful for Resolver as well,
          // i.e. Tokenizer informs that instruction was not started/completed
          // correctly. At the moment i will leave this for future to be fixed.
-         tok @ T::Error(..) => { IncludeResult::Failed(tok) }
+         T::Error(..) => { IncludeResult::Failed(None) }
 
-         tok @ T::Warning(..) => { IncludeResult::Progress(tok) }
+         T::Warning(..) => { IncludeResult::Progress }
       }
    }
 
@@ -401,164 +530,140 @@ impl IncludeResolver {
    // chunks.
    #[inline(always)]
    fn next_resolve_include_expect_close_paren(&mut self) -> IncludeResult {
-      let t = &mut self.tokenizer;
-
       use Token as T;
       use TokenBody as Tb;
-      use IncludeResolverSubState as SS;
 
       // This construct is used, so that we have less deep indentation.
-      let token = if let Some(token) = t.next() { token }
-      else {
-         // There must exist closing parenthesis for us to allow instruction
-         // to be resolved.
-
-         let tok = IncludeResult::Failed(Token::Error(
-            ParseError::OpenInstruction(Source {
-               pos_zero: self.include_pos_zero.unwrap_or(0),
-               component: Component::IncludeResolver,
-               line: line!(),
-               code: 3,
-            })
-         ));
-
-         return tok;
+      let token = match self.batch_peek() {
+         Ok(Some(token)) => token,
+         Ok(None) => {
+            // There must exist closing parenthesis for us to allow instruction
+            // to be resolved.
+
+            let tok = IncludeResult::Failed(Some(Token::Error(
+               ParseError::OpenInstruction(Source {
+                  pos_zero: self.include_pos_zero.unwrap_or(0),
+                  component: Component::IncludeResolver,
+                  line: line!(),
+                  code: 3,
+               })
+            )));
+
+            return tok;
+         }
+         Err(etoken) => return IncludeResult::Failed(Some(etoken)),
       };
 
       match token {
          T::Real(body) => match body {
-            tok @ Tb::CloseParen(..) => {
+            Tb::CloseParen(..) => {
                #[cfg(feature = "dbg_include_resolver_verbose")] {
                   println!("Resolver: close paren received, can really include");
                }
 
                if let Some(span) = self.tokenspan_file {
                   self.tokenspan_file = None;
-                  t.state_set(TokenizerState::ExpectDefered);
-                  let slice = t.span_slice(&span);
+                  let slice = self.tokenizer.span_slice(&span);
 
                   #[cfg(feature = "dbg_include_resolver_verbose")] {
                      println!("Resolver: convert span to string filename");
                      println!("Resolver: filename slice: {:?}", slice);
                   }
 
-                  if let Some(slice) = slice {
-                        let fn_as_str = std::str::from_utf8(slice).unwrap().to_owned();
+                  if let Ok(slice) = slice {
+                        let fn_as_str = match std::str::from_utf8(slice) {
+                           Ok(fn_as_str) => fn_as_str.to_owned(),
+                           Err(e) => {
+                              // File path can not be resolved, thus include
+                              // does not happen, the same way as for missing
+                              // path.
+                              return IncludeResult::Failed(Some(Token::Error(
+                                 ParseError::InvalidUtf8(Source {
+                                    pos_zero: span.pos_zero + e.valid_up_to(),
+                                    component: Component::IncludeResolver,
+                                    line: line!(),
+                                    code: 8,
+                                 })
+                              )));
+                           }
+                        };
                         #[cfg(feature = "dbg_include_resolver_verbose")] {
                            println!("filename to include: {}", fn_as_str);
                         }
 
-                        if let Err(..) = self.file_read(&fn_as_str) {
-                           // TODO: here the challenge is that depending on
-                           // instruction different action must be taken,
-                           // @include returns warnings, @require returns
-                           // errors.
-
-                           println!("Error reading file"); // TODO:
-                        }
+                        // File is read when batch is taken from Tokenizer,
+                        // since source can not be pushed while batch Tokens
+                        // are peeked.
+                        self.include_file = Some(fn_as_str);
                   }
                   else {
                         // This error should never happen unless there is a bug
                         // in code. Tokenizer should always be able to extract
                         // span slice for it's returned Tokens.
 
-                        if let Err(etoken) = self.batchbuf.append(Token::Real(tok)) {
-                           self.state = IncludeResolverState::Failed;
-
-                           return IncludeResult::Failed(etoken);
-                        }
-
-                        let retok = IncludeResult::Failed(Token::Error(
+                        let retok = IncludeResult::Failed(Some(Token::Error(
                            ParseError::InternalError(Source {
                               pos_zero: self.include_pos_zero.unwrap_or(0),
                               component: Component::IncludeResolver,
                               line: line!(),
                               code: 4,
                            })
-                        ));
+                        )));
 
                         return retok;
                   }
 
-                  self.substate = SS::Uninitialized;
-                  self.state = IncludeResolverState::Passthrough;
-
-                  return IncludeResult::Finalized(Token::Real(tok));
+                  IncludeResult::Finalized
                }
                else {
                   // TODO: return warning that include without file name is
                   // ignored and change state to parse defered?
                   println!("Resolver: not impl. return warning on empty include");
 
-                  // Since Resolver could not fulfill @include instruction, it
-                  // goes into pass-through state.
-                  self.state = IncludeResolverState::Passthrough;
-                  self.substate = IncludeResolverSubState::Uninitialized;
-
-                  // We still return Phantom token here, since all parenthesis
-                  // were matched and this would yield common behavior.
-                  // Otherwise outer code would receive some Phantom tokens for
-                  // '@include', '(' and Real token for ')'. That would seem
-                  // weird.
-                  // In this case it receives all tokens as Phantom. It is just
-                  // that include did not happen, so it is replaced with
-                  // nothing.
+                  // Since Resolver could not fulfill @include instruction,
+                  // batch is returned as-is with error.
 
-                  if let Err(etoken) = self.batchbuf.append(Token::Real(tok)) {
-                     self.state = IncludeResolverState::Failed;
-
-                     return IncludeResult::Failed(etoken);
-                  }
-
-                  let retok = IncludeResult::Failed(Token::Error(
+                  let retok = IncludeResult::Failed(Some(Token::Error(
                      ParseError::InstructionMissingArgs(Source {
                         pos_zero: self.include_pos_zero.unwrap_or(0),
                         component: Component::IncludeResolver,
                         line: line!(),
                         code: 5,
                      })
-                  ));
+                  )));
 
-                  return retok;
+                  retok
                }
             }
 
-            tok => {
+            _ => {
                // TODO: here we should actually return some error and warning
                // tokens as well.
 
-               // Since this was unexpected token, Resolver switches back to
-               // pass-through state. @include instruction could not be
-               // satisfied.
-               self.state = IncludeResolverState::Passthrough;
-               self.substate = IncludeResolverSubState::Uninitialized;
-
-               // TODO: think about correct behavior. At the moment '@include'
-               // '(' and 'file-path' are returned as Phantom already. We got
-               // unexpected Token instead of '('. Should we return it as Real
-               // token, Phantom?
-
-               IncludeResult::Failed(T::Real(tok))
+               // Since this was unexpected token, @include instruction could
+               // not be satisfied. Batch Tokens, including this one, are
+               // returned as-is.
+               IncludeResult::Failed(None)
             }
          }
 
          // At the moment IncludeResolver ignores any Phantom token.
-         tok @ T::Phantom(..) => { IncludeResult::Progress(tok) }
+         T::Phantom(..) => { IncludeResult::Progress }
 
          // StateChange is silently passed through, because it should not
          // influence IncludeResolver.
-         tok @ T::StateChange => { IncludeResult::Progress(tok) }
+         T::StateChange => { IncludeResult::Progress }
 
          // If Tokenizer has returned Fatal, even if next() is called again,
          // it would return Fatal. Thus Resolver is allowed to be transparent.
-         tok @ T::Fatal(..) => { IncludeResult::Failed(tok) }
+         T::Fatal(..) => { IncludeResult::Failed(None) }
 
          // TODO: There are some errors that can be useful for Resolver as well,
          // i.e. Tokenizer informs that instruction was not started correctly.
          // At the moment i will leave this for future to be fixed.
-         tok @ T::Error(..) => { IncludeResult::Failed(tok) }
+         T::Error(..) => { IncludeResult::Failed(None) }
 
-         tok @ T::Warning(..) => { IncludeResult::Progress(tok) }
+         T::Warning(..) => { IncludeResult::Progress }
       }
    }
 
@@ -592,112 +697,95 @@ impl IncludeResolver {
 
 
 
-   // Function that is called when IncludeResolver has collected all @include
-   // necessary tokens and moves translated Tokens from batchbuf to tokenbuf.
+   // Take peeked batch Tokens from Tokenizer and push them to tokenbuf after
+   // @include, as Phantom if phantom is set, otherwise as-is. StateChange is
+   // dropped. After this Tokenizer has no peeked Tokens, thus source can be
+   // pushed.
    //
-   // Function returns first returnable token, normally it is OpenParen.
+   // # Return
+   //
+   // * 'Err(Token)' - Token could not be buffered, or Tokenizer did not return
+   //   peeked Token. IncludeResolver is failed then.
    #[inline(always)]
-   fn next_resolve_include_finalized(&mut self) -> Option<Token> {
-      // When returning list of tokens, we must hold first item for returning
-      // and push all other items into tokenbuf, but maybe transformed
-      let mut firstitem: Option<Token> = None;
-
-      // Actually this function should not have been called if pos_zero is None.
-      let pos_zero = self.include_pos_zero.unwrap_or(0);
+   fn batch_take(&mut self, phantom: bool) -> Result<(), Token> {
+      let cnt_peeked = self.cnt_peeked;
+      self.cnt_peeked = 0;
+
+      for _ in 0..cnt_peeked {
+         let tok = match self.tokenizer.next() {
+            Some(tok) => tok,
+            None => {
+               // Tokenizer must return every peeked Token, unless there is a
+               // bug in code.
+               self.state = IncludeResolverState::Failed;
 
-      self.include_pos_zero = None;
+               return Err(Token::Fatal(ParseError::InternalError(Source {
+                  pos_zero: self.include_pos_zero.unwrap_or(0),
+                  component: Component::IncludeResolver,
+                  line: line!(),
+                  code: 11,
+               })));
+            }
+         };
 
-      #[cfg(not(feature = "unguarded_include_resolver_integrity"))] {
-         if self.batchbuf.buf_len() < 1 {
-            self.state = IncludeResolverState::Failed;
+         #[cfg(feature = "dbg_include_resolver_verbose")] {
+            println!("Resolver take batch Token: {:?}", tok);
+         }
 
-            // In this case, there is nothing to be returned, batch buf is
-            // emtpy, this function should not have been called at all.
+         let tok = match tok {
+            Token::Real(tbody) if phantom => Token::Phantom(tbody),
+            Token::StateChange => continue,
+            tok => tok,
+         };
 
-            return Some(Token::Fatal(ParseError::InternalError(Source {
-               pos_zero: pos_zero,
-               component: Component::IncludeResolver,
-               line: line!(),
-               code: 7,
-            })))
+         if let Err(etoken) = self.tokenbuf.push_back(tok) {
+            self.state = IncludeResolverState::Failed;
+            return Err(etoken);
          }
       }
 
-      let batchbuf = &mut self.batchbuf;
-      loop {
-         match batchbuf.popleft() {
-            Ok(None) => {
-               break;
-            }
+      Ok(())
+   }
 
-            Ok(Some(tok)) => {
-               if let Some(..) = firstitem {
-                  #[cfg(feature = "dbg_include_resolver_verbose")] {
-                     println!("Resolver finalize nth Token: {:?}", tok);
-                  }
 
-                  match tok {
-                     Token::Real(tbody) => {
-                        let itoken = Token::Phantom(tbody);
 
-                        if let Err(error_token) = self.tokenbuf.append(itoken) {
-                           // This is really bad error, no memory or similar.
-                           // There is nothing much we can do. Token is lost.
-                           self.state = IncludeResolverState::Failed;
-                           return Some(error_token);
-                        }
-                     }
-
-                     p @ Token::Phantom(..)
-                     | p @ Token::Error(..)
-                     | p @ Token::Fatal(..)
-                     | p @ Token::Warning(..)
-                     => {
-                        if let Err(error_token) = self.tokenbuf.append(p) {
-                           self.state = IncludeResolverState::Failed;
-                           return Some(error_token);
-                        }
-                     }
+   // Function that is called when IncludeResolver has collected all @include
+   // necessary tokens. Batch is taken from Tokenizer as Phantom and included
+   // file is pushed to Tokenizer.
+   //
+   // Function returns first returnable token, normally it is Phantom @include,
+   // the rest are returned from tokenbuf by Iterator.
+   #[inline(always)]
+   fn next_resolve_include_finalized(&mut self) -> Option<Token> {
+      self.state = IncludeResolverState::Passthrough;
+      self.substate = IncludeResolverSubState::Uninitialized;
 
-                     Token::StateChange => {}
-                  }
-               }
-               else {
-                  #[cfg(feature = "dbg_include_resolver_verbose")] {
-                     println!("Resolver finalize first Token: {:?}", tok);
-                  }
+      if let Err(etoken) = self.batch_take(true) {
+         return Some(etoken);
+      }
 
-                  match tok {
-                     Token::Real(tbody) => {
-                        firstitem = Some(Token::Phantom(tbody));
-                     }
+      // Actually this function should not have been called if pos_zero is None.
+      let pos_zero = self.include_pos_zero.unwrap_or(0);
 
-                     p @ Token::Phantom(..)
-                     | p @ Token::Error(..)
-                     | p @ Token::Fatal(..)
-                     | p @ Token::Warning(..)
-                     => {
-                        firstitem = Some(p)
-                     }
+      self.include_pos_zero = None;
 
-                     Token::StateChange => {}
-                  }
-               }
-            }
+      if let Some(filename) = self.include_file.take() {
+         if let Err(..) = self.file_read(&filename) {
+            // TODO: here the challenge is that depending on
+            // instruction different action must be taken,
+            // @include returns warnings, @require returns
+            // errors.
 
-            Err(tok) => {
-               self.state = IncludeResolverState::Failed;
-               return Some(tok);
-            }
+            println!("Error reading file"); // TODO:
          }
       }
 
-      if let Some(tok) = firstitem {
+      if let Some(tok) = self.tokenbuf.pop_front() {
          Some(tok)
       }
       else {
-         // This case is impossible unless there is an error in code in this
-         // function.
+         // This case is impossible unless there is an error in code, since
+         // tokenbuf holds at least @include.
 
          self.state = IncludeResolverState::Failed;
 
@@ -712,96 +800,85 @@ impl IncludeResolver {
 
 
 
+   // Function that is called when collected batch does not satisfy @include.
+   // Tokens are returned as-is together with error Token that broke the batch,
+   // and IncludeResolver goes into pass-through state.
+   //
+   // Function returns first token from batch, normally it is Phantom @include,
+   // the rest are returned from tokenbuf by Iterator.
+   #[inline(always)]
+   fn next_resolve_include_failed(&mut self, error: Option<Token>) -> Option<Token> {
+      self.state = IncludeResolverState::Passthrough;
+      self.substate = IncludeResolverSubState::Uninitialized;
+      self.tokenspan_file = None;
+      self.include_file = None;
+
+      if let Err(etoken) = self.batch_take(false) {
+         return Some(etoken);
+      }
+
+      self.include_pos_zero = None;
+
+      if let Some(error) = error {
+         if let Err(etoken) = self.tokenbuf.push_back(error) {
+            self.state = IncludeResolverState::Failed;
+            return Some(etoken);
+         }
+      }
+
+      self.tokenbuf.pop_front()
+   }
+
+
+
    // Function that is called from Iterator when IncludeResolver is in state
    // ResolveInclude.
    //
    // The goal for this construct is to split code in readable chunks.
    //
-   // Function tries to collect a batch of tokens from Tokenizer and if
-   // collected batch satisfies @include, all tokens are transformed to Phantom
-   // and returned (done by next_resolve_include_finalized).
+   // Function peeks a batch of tokens from Tokenizer and if peeked batch
+   // satisfies @include, all tokens are transformed to Phantom and returned
+   // (done by next_resolve_include_finalized).
    #[inline(always)]
    fn next_resolve_include(&mut self) -> Option<Token> {
       // IncludeResolver must transform Token::Real to Token::Phantom if it
       // resolves file inclusion, otherwise it should return Tokens as-is.
       //
-      // To achieve that, IncludeResolver buffers tokens until whole instruction
-      // is assembled and only then returns.
+      // To achieve that, IncludeResolver peeks tokens until whole instruction
+      // is assembled and only then takes them from Tokenizer, see
+      // DD-2026-10-16-08.
 
       #[cfg(not(feature = "unguarded_include_resolver_integrity"))] {
-         if self.batchbuf.buf_len() != 1 {
+         if self.tokenbuf.num_tokens() != 1 || self.cnt_peeked != 0 {
             // TODO: do not panic, set state to failed and return error token?
-            panic!("batchbuf should be empty!");
+            panic!("tokenbuf should hold only @include!");
          }
       }
 
-      enum BreakReason { Finalized, Failed }
-
-      let break_reason = loop {
+      loop {
          match self.next_resolve_include_by_substate() {
-            IncludeResult::Progress(token) => {
+            IncludeResult::Progress => {
                #[cfg(feature = "dbg_include_resolver_verbose")] {
-                  println!("Resolver collect Progress/Token: {:?}", token);
-               }
-
-               match token {
-                  Token::StateChange => {}
-                  _ => {
-                     if let Err(e) = self.batchbuf.append(token) {
-                        self.state = IncludeResolverState::Failed;
-                        return Some(e);
-                     }
-                  }
+                  println!("Resolver peek Progress, peeked: {}", self.cnt_peeked);
                }
             }
 
-            IncludeResult::Finalized(token) => {
+            IncludeResult::Finalized => {
                #[cfg(feature = "dbg_include_resolver_verbose")] {
-                  println!("Resolver collect Finalized/Token: {:?}", token);
-               }
-
-               match token {
-                  Token::StateChange => {}
-                  _ => {
-                     if let Err(e) = self.batchbuf.append(token) {
-                        self.state = IncludeResolverState::Failed;
-                        return Some(e);
-                     }
-                  }
+                  println!("Resolver peek Finalized, peeked: {}", self.cnt_peeked);
                }
 
-               break BreakReason::Finalized;
+               return self.next_resolve_include_finalized();
             }
 
-            IncludeResult::Failed(token) => {
+            IncludeResult::Failed(error) => {
                #[cfg(feature = "dbg_include_resolver_verbose")] {
-                  println!("Resolver collect Failed/Token: {:?}", token);
+                  println!("Resolver peek Failed/Token: {:?}", error);
                }
 
-               match token {
-                  Token::StateChange => {}
-                  _ => {
-                     if let Err(e) = self.batchbuf.append(token) {
-                        self.state = IncludeResolverState::Failed;
-                        return Some(e);
-                     }
-                  }
-               }
-
-               break BreakReason::Failed;
+               return self.next_resolve_include_failed(error);
             }
          }
-      };
-
-      match break_reason {
-         BreakReason::Finalized => {
-            self.next_resolve_include_finalized()
-         }
-
-         BreakReason::Failed => {
-            // TODO: return Real tokens on error as-is.
-            None
-         }
       }
    }
 }
@@ -4,6 +4,11 @@ use crate::{
       IncludeResolverState,
    },
    token::Token,
+   parse_error::{
+      ParseError,
+      Component,
+      Source,
+   },
 };
 
 
@@ -12,16 +17,40 @@ impl Iterator for IncludeResolver {
    type Item = Token;
 
    fn next(&mut self) -> Option<Self::Item> {
-      use IncludeResolverState as S;
-
-      match self.tokenbuf.popleft() {
-         Ok(None) => {},
+      let token = self.token_next();
 
-         Ok(some_token) => {
-            return some_token;
+      match &token {
+         Some(Token::Error(e)) => {
+            self.cnt_error += 1;
+            if self.cnt_error > self.error_limit {
+               return Some(self.error_limit_fail(e.source(), 9));
+            }
          }
+         Some(Token::Warning(e)) => {
+            self.cnt_warning += 1;
+            if self.cnt_warning > self.warning_limit {
+               return Some(self.error_limit_fail(e.source(), 10));
+            }
+         }
+         _ => {}
+      }
+
+      token
+   }
+}
+
+
+
+impl IncludeResolver {
+   // Returns the next Token for Iterator, before error and warning limits are
+   // applied.
+   fn token_next(&mut self) -> Option<Token> {
+      use IncludeResolverState as S;
 
-         Err(token) => {
+      // While @include is being resolved, tokenbuf holds collected batch,
+      // which must not be returned before it is complete.
+      if let S::Passthrough | S::Failed = self.state {
+         if let Some(token) = self.tokenbuf.pop_front() {
             return Some(token);
          }
       }
@@ -42,4 +71,34 @@ impl Iterator for IncludeResolver {
          }
       }
    }
-}
\ No newline at end of file
+
+
+
+   // Fail IncludeResolver, when Error or Warning Token over the limit is
+   // received. That Token is not returned and neither are buffered Tokens. The
+   // number of suppressed Error and Warning Tokens is returned within
+   // ErrorLimit, at the position of the Token over the limit.
+   fn error_limit_fail(&mut self, source: Option<&Source>, code: u16) -> Token {
+      let pos_zero = match source {
+         Some(source) => source.pos_zero,
+         None => 0,
+      };
+
+      let mut cnt_suppressed = 1;
+
+      while let Some(token) = self.tokenbuf.pop_front() {
+         if let Token::Error(..) | Token::Warning(..) = token {
+            cnt_suppressed += 1;
+         }
+      }
+
+      self.state = IncludeResolverState::Failed;
+
+      Token::Fatal(ParseError::ErrorLimit(Source {
+         pos_zero: pos_zero,
+         component: Component::IncludeResolver,
+         line: line!(),
+         code: code,
+      }, cnt_suppressed))
+   }
+}
@@ -46,6 +46,20 @@ pub struct Source {
 
 
 
+/// Suggestion how to fix template source, that is returned together with some
+/// ParseError variants.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Suggestion {
+   /// Replace what was written with given instruction name, i.e. "include"
+   /// for "@Include(", "@inclde(" or "@ include(".
+   Instruction(&'static str),
+
+   /// Escape @ symbol as "@@", since it does not start any known instruction.
+   EscapeAt,
+}
+
+
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum ParseError {
    /// This error is returned when memory could not be allocated. This is
@@ -67,14 +81,101 @@ pub enum ParseError {
 
    InstructionError(Source),
 
+   /// This warning is returned after UnescapedAt, when @ symbol looks like
+   /// instruction, but is not, i.e. "@Include(", "@inclde(", "@ include(" or
+   /// "@foo(". Suggestion tells if user should fix instruction name or escape
+   /// @ symbol.
+   InstructionNearMiss(Source, Suggestion),
+
    /// This error is returned, when instruction is opened, but not closed, i.e.
    /// "@include(".
    OpenInstruction(Source),
 
+   /// This error is returned, when tag is opened, but not closed, i.e.
+   /// "<div class='x'" at the end of template source.
+   OpenTag(Source),
+
+   /// This error is returned, when interpolation is opened, but not closed,
+   /// i.e. "{{ name" or "{!! name" at the end of template source.
+   OpenInterpolation(Source),
+
+   /// This error is returned, when template comment is opened, but not closed,
+   /// i.e. "{{-- note" at the end of template source. pos_zero points to
+   /// opening "{{--".
+   OpenComment(Source),
+
+   /// This error is returned, when "@verbatim" block is not closed with
+   /// "@endverbatim". pos_zero points to "@verbatim".
+   OpenVerbatim(Source),
+
+   /// This error is returned, when HTML comment is opened, but not closed,
+   /// i.e. "<!-- note" at the end of template source. pos_zero points to
+   /// "<!--".
+   OpenHtmlComment(Source),
+
+   /// This error is returned, when CDATA section is opened, but not closed.
+   /// pos_zero points to "<![CDATA[".
+   OpenCdata(Source),
+
+   /// This error is returned, when string literal within instruction arguments
+   /// is not closed before the end of line, i.e. "@include('x)". pos_zero
+   /// points to opening quote.
+   OpenString(Source),
+
+   /// This warning is returned, when "<" within text can not start a tag, i.e.
+   /// "a < b". Most probably user wanted to write "&lt;" instead.
+   UnescapedLt(Source),
+
+   /// This warning is returned, when ">" within text does not close any tag,
+   /// i.e. "a > b". Most probably user wanted to write "&gt;" instead.
+   UnescapedGt(Source),
+
    /// This error is returned when Iterator is built but no input was loaded
    /// for tokenizer.
    NoInput(Source),
 
+   /// This error is returned right after Token, that envelops the first
+   /// invalid UTF-8 byte in template source region, i.e. when binary or
+   /// Latin-1 file was included by mistake. pos_zero points to that byte.
+   /// Tokenizer continues to tokenize source as bytes, only the first invalid
+   /// byte per region is reported.
+   InvalidUtf8(Source),
+
+   /// This warning is returned right after Newline, that has different line
+   /// ending than the first Newline in the same template source region, i.e.
+   /// "\r\n" in file that otherwise uses "\n". Only the first such Newline
+   /// per region is reported. See DD-2026-10-16-02.
+   MixedNewline(Source),
+
+   /// This error is returned when custom Syntax is built with instruction
+   /// sigil or delimiters, that can not be used. See Syntax::custom.
+   InvalidDelimiter(Source),
+
+   /// This fatal error is returned instead of Error or Warning Token, that
+   /// exceeds limit set with error_limit_set or warning_limit_set, i.e. when
+   /// binary or badly broken file is tokenized. usize is the number of Error
+   /// and Warning Tokens, that were suppressed: the one over the limit and
+   /// those, that were already buffered. Source after it is not tokenized.
+   /// See DD-2026-10-16-06.
+   ErrorLimit(Source, usize),
+
+   /// This error is returned when streaming input functions are called in
+   /// wrong order, i.e. src_append or src_close is called when there is no
+   /// open region, or src_push_open is called while another region is still
+   /// open. See Tokenizer::src_push_open.
+   StreamError(Source),
+
+   /// This error is returned when Tokenizer lookahead is used in wrong order,
+   /// i.e. rewind is called with Checkpoint, that is already released or made
+   /// before reset, or src_push is called while Tokens returned by peek are
+   /// not consumed yet. See Tokenizer::checkpoint.
+   LookaheadError(Source),
+
+   /// This error is returned by Tokenizer::span_slice, when Span points into
+   /// region, whose bytes are released with region_release, or into region
+   /// from before reset. pos_zero is Span's pos_zero.
+   RegionReleased(Source),
+
    /// When there is an unwanted whitespace in template source.
    UnwantedWhiteSpace(Source),
 
@@ -85,4 +186,46 @@ pub enum ParseError {
 
 
 
+impl ParseError {
+   /// Returns Source of this error, None for ParseError::None.
+   pub fn source(&self) -> Option<&Source> {
+      use ParseError as Pe;
+
+      match self {
+         Pe::NoMemory(source)
+         | Pe::InternalError(source)
+         | Pe::InstructionNotOpen(source)
+         | Pe::InstructionMissingArgs(source)
+         | Pe::InstructionError(source)
+         | Pe::InstructionNearMiss(source, ..)
+         | Pe::OpenInstruction(source)
+         | Pe::OpenTag(source)
+         | Pe::OpenInterpolation(source)
+         | Pe::OpenComment(source)
+         | Pe::OpenVerbatim(source)
+         | Pe::OpenHtmlComment(source)
+         | Pe::OpenCdata(source)
+         | Pe::OpenString(source)
+         | Pe::UnescapedLt(source)
+         | Pe::UnescapedGt(source)
+         | Pe::NoInput(source)
+         | Pe::InvalidUtf8(source)
+         | Pe::MixedNewline(source)
+         | Pe::InvalidDelimiter(source)
+         | Pe::ErrorLimit(source, ..)
+         | Pe::StreamError(source)
+         | Pe::LookaheadError(source)
+         | Pe::RegionReleased(source)
+         | Pe::UnwantedWhiteSpace(source)
+         => {
+            Some(source)
+         }
+
+         Pe::None => None,
+      }
+   }
+}
+
+
+
 // ================== EOF: do not write below this ============================
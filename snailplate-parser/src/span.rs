@@ -39,3 +39,108 @@ pub trait SpanFormatter {
 }
 
 
+
+
+
+/// Character based location of Span within its line. Span stores only byte
+/// positions, because that is what Tokenizer needs and each Token carries a
+/// Span, thus we do not want to grow it, see DD-2023-07-01-01. Columns are
+/// calculated on demand from source bytes, i.e. when diagnostic message is
+/// printed, see Tokenizer::span_columns.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SpanColumns {
+   /// Number of UTF-8 characters in line before Span.
+   pub column: usize,
+
+   /// Number of UTF-8 characters in Span.
+   pub length: usize,
+
+   /// Display width of line before Span, i.e. terminal column at which Span
+   /// starts. Tabs are expanded to the next tab stop, wide characters (CJK,
+   /// emoji) take two columns, combining marks take none.
+   pub column_width: usize,
+
+   /// Display width of Span.
+   pub width: usize,
+}
+
+
+
+/// Calculate SpanColumns for Span within given region source.
+///
+/// Invalid UTF-8 byte sequences are counted the same way as
+/// String::from_utf8_lossy replaces them, one character per sequence. Newline
+/// within Span has no width.
+///
+/// # Return
+///
+/// * None - if Span is out of bounds for given source.
+/// * Some(SpanColumns) - otherwise.
+pub fn span_columns(src: &[u8], span: &Span, tab_width: usize) -> Option<SpanColumns> {
+   if span.pos_line > span.pos_region || span.pos_region + span.length > src.len() {
+      return None;
+   }
+
+   let pos_line_start = span.pos_region - span.pos_line;
+   let before = String::from_utf8_lossy(&src[pos_line_start..span.pos_region]);
+   let body = String::from_utf8_lossy(&src[span.pos_region..span.pos_region + span.length]);
+
+   let mut column = 0;
+   let mut column_width = 0;
+   for ch in before.chars() {
+      column += 1;
+      column_width += char_width(ch, column_width, tab_width);
+   }
+
+   let mut length = 0;
+   let mut width = 0;
+   for ch in body.chars() {
+      length += 1;
+      width += char_width(ch, column_width + width, tab_width);
+   }
+
+   Some(SpanColumns {
+      column: column,
+      length: length,
+      column_width: column_width,
+      width: width,
+   })
+}
+
+
+
+// Returns display width for character that is shown at given display column.
+// Tab width of 0 is handled as 1.
+//
+// Wide character ranges are a rough subset of Unicode East Asian Wide and Full
+// width ranges, that covers CJK and emoji. That is enough for diagnostics,
+// there is no need for full Unicode tables.
+#[inline(always)]
+fn char_width(ch: char, column: usize, tab_width: usize) -> usize {
+   match ch {
+      '\t' => {
+         let tab_width = tab_width.max(1);
+         tab_width - column % tab_width
+      }
+
+      '\n' | '\r'
+      | '\u{0300}'..='\u{036F}' // combining diacritical marks
+      | '\u{200B}'..='\u{200F}' // zero width space, joiners, marks
+      | '\u{FE00}'..='\u{FE0F}' // variation selectors
+      => 0,
+
+      '\u{1100}'..='\u{115F}' // Hangul Jamo
+      | '\u{2E80}'..='\u{A4CF}' // CJK, Kana, Yi
+      | '\u{AC00}'..='\u{D7A3}' // Hangul syllables
+      | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs
+      | '\u{FE30}'..='\u{FE4F}' // CJK compatibility forms
+      | '\u{FF00}'..='\u{FF60}' // fullwidth forms
+      | '\u{FFE0}'..='\u{FFE6}'
+      | '\u{1F300}'..='\u{1F64F}' // pictographs, emoticons
+      | '\u{1F900}'..='\u{1F9FF}' // supplemental pictographs
+      | '\u{20000}'..='\u{3FFFD}' // CJK extensions
+      => 2,
+
+      _ => 1,
+   }
+}
@@ -54,7 +54,7 @@ pub enum Token {
 
 
 impl Token {
-   pub fn fmt<'a, F: SpanFormatter>(&'a self, bufowner: &'a F) -> TokenFormatWrapper<F> {
+   pub fn fmt<'a, F: SpanFormatter>(&'a self, bufowner: &'a F) -> TokenFormatWrapper<'a, F> {
       TokenFormatWrapper(self, bufowner)
    }
 
@@ -77,6 +77,23 @@ impl Token {
          => match parse_error {
             Pe::InstructionError(..) 
             | Pe::OpenInstruction(..)
+            | Pe::OpenTag(..)
+            | Pe::OpenInterpolation(..)
+            | Pe::OpenComment(..)
+            | Pe::OpenVerbatim(..)
+            | Pe::OpenHtmlComment(..)
+            | Pe::OpenCdata(..)
+            | Pe::StreamError(..)
+            | Pe::LookaheadError(..)
+            | Pe::RegionReleased(..)
+            | Pe::InvalidUtf8(..)
+            | Pe::MixedNewline(..)
+            | Pe::InvalidDelimiter(..)
+            | Pe::OpenString(..)
+            | Pe::InstructionNearMiss(..)
+            | Pe::ErrorLimit(..)
+            | Pe::UnescapedLt(..)
+            | Pe::UnescapedGt(..)
             | Pe::InstructionNotOpen(..)
             | Pe::InstructionMissingArgs(..)
             | Pe::UnwantedWhiteSpace(..)
@@ -116,7 +133,11 @@ impl<'a, F: SpanFormatter> std::fmt::Debug for TokenFormatWrapper<'a, F> {
          ($case:tt, $parse_error:tt, $source:tt) => {(
             Some(concat!(stringify!($case), "(", stringify!($parse_error), "(")),
             Some(format!("{:?}", $source)), Some("))"), None
-         )}
+         )};
+         ($case:tt, $parse_error:tt, $source:tt, $extra:tt) => {(
+            Some(concat!(stringify!($case), "(", stringify!($parse_error), "(")),
+            Some(format!("{:?}, {:?}", $source, $extra)), Some("))"), None
+         )};
       }
 
       let (start, mid, end, body) = match self.0 {
@@ -125,6 +146,40 @@ impl<'a, F: SpanFormatter> std::fmt::Debug for TokenFormatWrapper<'a, F> {
          T::Fatal(parse_error) => match parse_error {
             Pe::OpenInstruction(source)
                => error_tuple!(Fatal, OpenInstruction, source),
+            Pe::OpenTag(source)
+               => error_tuple!(Fatal, OpenTag, source),
+            Pe::OpenInterpolation(source)
+               => error_tuple!(Fatal, OpenInterpolation, source),
+            Pe::OpenComment(source)
+               => error_tuple!(Fatal, OpenComment, source),
+            Pe::OpenVerbatim(source)
+               => error_tuple!(Fatal, OpenVerbatim, source),
+            Pe::OpenHtmlComment(source)
+               => error_tuple!(Fatal, OpenHtmlComment, source),
+            Pe::OpenCdata(source)
+               => error_tuple!(Fatal, OpenCdata, source),
+            Pe::StreamError(source)
+               => error_tuple!(Fatal, StreamError, source),
+            Pe::LookaheadError(source)
+               => error_tuple!(Fatal, LookaheadError, source),
+            Pe::RegionReleased(source)
+               => error_tuple!(Fatal, RegionReleased, source),
+            Pe::InvalidUtf8(source)
+               => error_tuple!(Fatal, InvalidUtf8, source),
+            Pe::MixedNewline(source)
+               => error_tuple!(Fatal, MixedNewline, source),
+            Pe::InvalidDelimiter(source)
+               => error_tuple!(Fatal, InvalidDelimiter, source),
+            Pe::OpenString(source)
+               => error_tuple!(Fatal, OpenString, source),
+            Pe::InstructionNearMiss(source, suggestion)
+               => error_tuple!(Fatal, InstructionNearMiss, source, suggestion),
+            Pe::ErrorLimit(source, cnt_suppressed)
+               => error_tuple!(Fatal, ErrorLimit, source, cnt_suppressed),
+            Pe::UnescapedLt(source)
+               => error_tuple!(Fatal, UnescapedLt, source),
+            Pe::UnescapedGt(source)
+               => error_tuple!(Fatal, UnescapedGt, source),
             Pe::InstructionError(source)
                => error_tuple!(Fatal, InstructionError, source),
             Pe::InstructionNotOpen(source)
@@ -147,6 +202,40 @@ impl<'a, F: SpanFormatter> std::fmt::Debug for TokenFormatWrapper<'a, F> {
          T::Error(parse_error) => match parse_error {
             Pe::OpenInstruction(source)
                => error_tuple!(Error, OpenInstruction, source),
+            Pe::OpenTag(source)
+               => error_tuple!(Error, OpenTag, source),
+            Pe::OpenInterpolation(source)
+               => error_tuple!(Error, OpenInterpolation, source),
+            Pe::OpenComment(source)
+               => error_tuple!(Error, OpenComment, source),
+            Pe::OpenVerbatim(source)
+               => error_tuple!(Error, OpenVerbatim, source),
+            Pe::OpenHtmlComment(source)
+               => error_tuple!(Error, OpenHtmlComment, source),
+            Pe::OpenCdata(source)
+               => error_tuple!(Error, OpenCdata, source),
+            Pe::StreamError(source)
+               => error_tuple!(Error, StreamError, source),
+            Pe::LookaheadError(source)
+               => error_tuple!(Error, LookaheadError, source),
+            Pe::RegionReleased(source)
+               => error_tuple!(Error, RegionReleased, source),
+            Pe::InvalidUtf8(source)
+               => error_tuple!(Error, InvalidUtf8, source),
+            Pe::MixedNewline(source)
+               => error_tuple!(Error, MixedNewline, source),
+            Pe::InvalidDelimiter(source)
+               => error_tuple!(Error, InvalidDelimiter, source),
+            Pe::OpenString(source)
+               => error_tuple!(Error, OpenString, source),
+            Pe::InstructionNearMiss(source, suggestion)
+               => error_tuple!(Error, InstructionNearMiss, source, suggestion),
+            Pe::ErrorLimit(source, cnt_suppressed)
+               => error_tuple!(Error, ErrorLimit, source, cnt_suppressed),
+            Pe::UnescapedLt(source)
+               => error_tuple!(Error, UnescapedLt, source),
+            Pe::UnescapedGt(source)
+               => error_tuple!(Error, UnescapedGt, source),
             Pe::InstructionError(source)
                => error_tuple!(Error, InstructionError, source),
             Pe::InstructionNotOpen(source)
@@ -169,6 +258,40 @@ impl<'a, F: SpanFormatter> std::fmt::Debug for TokenFormatWrapper<'a, F> {
          T::Warning(parse_error) => match parse_error {
             Pe::OpenInstruction(source)
                => error_tuple!(Warning, OpenInstruction, source),
+            Pe::OpenTag(source)
+               => error_tuple!(Warning, OpenTag, source),
+            Pe::OpenInterpolation(source)
+               => error_tuple!(Warning, OpenInterpolation, source),
+            Pe::OpenComment(source)
+               => error_tuple!(Warning, OpenComment, source),
+            Pe::OpenVerbatim(source)
+               => error_tuple!(Warning, OpenVerbatim, source),
+            Pe::OpenHtmlComment(source)
+               => error_tuple!(Warning, OpenHtmlComment, source),
+            Pe::OpenCdata(source)
+               => error_tuple!(Warning, OpenCdata, source),
+            Pe::StreamError(source)
+               => error_tuple!(Warning, StreamError, source),
+            Pe::LookaheadError(source)
+               => error_tuple!(Warning, LookaheadError, source),
+            Pe::RegionReleased(source)
+               => error_tuple!(Warning, RegionReleased, source),
+            Pe::InvalidUtf8(source)
+               => error_tuple!(Warning, InvalidUtf8, source),
+            Pe::MixedNewline(source)
+               => error_tuple!(Warning, MixedNewline, source),
+            Pe::InvalidDelimiter(source)
+               => error_tuple!(Warning, InvalidDelimiter, source),
+            Pe::OpenString(source)
+               => error_tuple!(Warning, OpenString, source),
+            Pe::InstructionNearMiss(source, suggestion)
+               => error_tuple!(Warning, InstructionNearMiss, source, suggestion),
+            Pe::ErrorLimit(source, cnt_suppressed)
+               => error_tuple!(Warning, ErrorLimit, source, cnt_suppressed),
+            Pe::UnescapedLt(source)
+               => error_tuple!(Warning, UnescapedLt, source),
+            Pe::UnescapedGt(source)
+               => error_tuple!(Warning, UnescapedGt, source),
             Pe::InstructionError(source)
                => error_tuple!(Warning, InstructionWarning, source),
             Pe::InstructionNotOpen(source)
@@ -8,6 +8,26 @@ use std::fmt;
 
 
 
+/// Describes where interpolation is used, since escaping of interpolated
+/// values depends on it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TextContext {
+   /// Interpolation is in text, i.e. "<p>{{ x }}</p>".
+   Text,
+
+   /// Interpolation is within a tag, i.e. "<a href=\"{{ x }}\">".
+   Tag,
+
+   /// Interpolation is within raw-text element content. Tags are not
+   /// tokenized there, see TokenizerState::ExpectRawTextEnd.
+   Script,
+   Style,
+   Textarea,
+   Title,
+}
+
+
+
 /// Structure that describes token type and span.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TokenBody {
@@ -19,6 +39,38 @@ pub enum TokenBody {
    /// be span overlaping "@include" exactly.
    Include(Span),
 
+   /// Instruction tokens below are matched the same way as Include. Those that
+   /// take arguments, like @if, are followed by OpenParen, instruction
+   /// arguments and CloseParen tokens. Instructions without arguments, like
+   /// @endif, envelop "@endif" exactly. @empty, @break and @continue have
+   /// arguments only if "(" follows instruction name right away.
+   Require(Span),
+   Extends(Span),
+   Section(Span),
+   EndSection(Span),
+   Yield(Span),
+   If(Span),
+   ElseIf(Span),
+   Else(Span),
+   EndIf(Span),
+   Unless(Span),
+   EndUnless(Span),
+   Foreach(Span),
+   EndForeach(Span),
+   For(Span),
+   EndFor(Span),
+   While(Span),
+   EndWhile(Span),
+   Empty(Span),
+   Break(Span),
+   Continue(Span),
+
+   /// This matches "@verbatim". Everything after it till "@endverbatim" is
+   /// returned as Defered and Newline tokens, thus instructions, tags and
+   /// interpolations within are passed to output unchanged.
+   Verbatim(Span),
+   EndVerbatim(Span),
+
    /// This matches tag starts, for example, "<div" in HTML.
    TagOpenStart(Span),
 
@@ -29,11 +81,52 @@ pub enum TokenBody {
    /// This matches the start of closing tag, example "</"
    TagCloseStart(Span),
 
-   /// This matches tag closes, like "</div>" or "</>" or "/>" if it follows
-   /// TagOpenStart.
+   /// This matches tag closes, like "</div>" or "</>".
    TagClose(Span),
 
-   // This is @@, while we know that it's len is always 2, reuse same structure.
+   /// This matches "/>", that ends self-closing tag after TagOpenStart and
+   /// attributes, i.e. "<br/>". Unlike TagOpenEnd, it does not open raw-text
+   /// element.
+   TagSelfClose(Span),
+
+   /// This matches attribute name within open tag, i.e. "href" in
+   /// "<a href='x'>".
+   AttrName(Span),
+
+   /// This matches "=" between attribute name and value.
+   AttrEq(Span),
+
+   /// This matches quote that opens or closes quoted attribute value, either
+   /// "'" or "\"".
+   AttrQuote(Span),
+
+   /// This matches attribute value without quotes, i.e. "x" in "<a href='x'>"
+   /// or "<a href=x>". If value contains instruction, interpolation or
+   /// newline, there is an AttrValue token for each part between them.
+   AttrValue(Span),
+
+   /// This matches HTML comment start "<!--". HTML comment is part of output,
+   /// thus instructions and interpolations within it are tokenized, see
+   /// DD-2026-10-16-01.
+   HtmlCommentOpen(Span),
+
+   /// This matches HTML comment end "-->".
+   HtmlCommentClose(Span),
+
+   /// This matches "<!DOCTYPE" in any character case. It is followed by the
+   /// same tokens as TagOpenStart, i.e. Defered and TagOpenEnd for " html>".
+   Doctype(Span),
+
+   /// This matches CDATA section start "<![CDATA[". CDATA section content is
+   /// tokenized the same way as HTML comment content.
+   CdataOpen(Span),
+
+   /// This matches CDATA section end "]]>".
+   CdataClose(Span),
+
+   /// This is @@, while we know that it's len is always 2, reuse same structure.
+   /// It is matched in text, within tags and within instruction arguments.
+   /// Renderer should output it as a single @ symbol.
    EscapedAt(Span),
 
    // Tis is @ symbol that is alone before whitespace or within unmatched
@@ -52,14 +145,25 @@ pub enum TokenBody {
    OpenParen(Span),
    CloseParen(Span),
 
+   /// This matches single or double quoted string literal within instruction
+   /// arguments, including quotes, i.e. "'a)b'". Backslash escapes are not
+   /// resolved by Tokenizer, they are just skipped while looking for closing
+   /// quote.
+   StringLiteral(Span),
+
    /// When < matched without any known context. This could be an erroneous
    /// tag start, or unescaped &lt; within HTML body. Thus warning or error
-   /// should be emitted.
+   /// should be emitted. Tokenizer emits ParseError::UnescapedLt warning
+   /// right after this token.
    Lt(Span),
+
+   /// When > matched without any known context, i.e. it does not close any
+   /// tag. Tokenizer emits ParseError::UnescapedGt warning right after this
+   /// token.
    Gt(Span),
 
    /// This matches characters that are considered to be whitespaces. For
-   /// example " \t\r", but does not include newline. See DD-2023-07-01-01.
+   /// example " \t", but does not include newline. See DD-2023-07-01-01.
    WhiteSpace(Span),
 
    /// This matches the same conditions as WhiteSpace, but only if the following
@@ -76,17 +180,47 @@ pub enum TokenBody {
    /// Whitespace that spans over whole line. DD-2023-07-15-01.
    WhiteSpaceWhole(Span),
 
-   /// This matches newlines, usually "\n" or "\r\n". See DD-2023-07-01-01.
+   /// This matches newlines "\n", "\r\n" or lone "\r". See DD-2023-07-01-01
+   /// and DD-2026-10-16-02.
    Newline(Span),
 
    /// This token describes template file path for @include, @require directive.
    FilePath(Span),
+
+   /// This matches "{{" that starts value interpolation. Interpolated values
+   /// are escaped by default, TextContext tells how.
+   InterpolationOpen(Span, TextContext),
+
+   /// This matches "}}" that closes value interpolation started with
+   /// InterpolationOpen.
+   InterpolationClose(Span),
+
+   /// This matches "{!!" that starts raw (unescaped) value interpolation. User
+   /// must explicitly ask for unescaped output, thus it has different syntax.
+   RawInterpolationOpen(Span, TextContext),
+
+   /// This matches "!!}" that closes raw value interpolation started with
+   /// RawInterpolationOpen.
+   RawInterpolationClose(Span),
+
+   /// This envelops expression between interpolation delimiters, i.e. " x " in
+   /// "{{ x }}". Whitespace around expression is not stripped. If expression
+   /// spans over multiple lines, there is an Expression token per line with
+   /// Newline tokens in between.
+   Expression(Span),
+
+   /// This matches template comment "{{-- note --}}" including delimiters.
+   /// Instructions and interpolations within comment are not tokenized.
+   /// Comment is not part of output, thus renderers should drop it. If comment
+   /// spans over multiple lines, there is a Comment token per line with
+   /// Newline tokens in between.
+   Comment(Span),
 }
 
 
 
 impl TokenBody {
-   pub fn fmt<'a, F: SpanFormatter>(&'a self, bufowner: &'a F) -> TokenBodyFormatWrapper<F> {
+   pub fn fmt<'a, F: SpanFormatter>(&'a self, bufowner: &'a F) -> TokenBodyFormatWrapper<'a, F> {
       TokenBodyFormatWrapper(self, bufowner)
    }
 
@@ -95,15 +229,48 @@ impl TokenBody {
 
       match &self {
          Tb::Include(span)
+         | Tb::Require(span)
+         | Tb::Extends(span)
+         | Tb::Section(span)
+         | Tb::EndSection(span)
+         | Tb::Yield(span)
+         | Tb::If(span)
+         | Tb::ElseIf(span)
+         | Tb::Else(span)
+         | Tb::EndIf(span)
+         | Tb::Unless(span)
+         | Tb::EndUnless(span)
+         | Tb::Foreach(span)
+         | Tb::EndForeach(span)
+         | Tb::For(span)
+         | Tb::EndFor(span)
+         | Tb::While(span)
+         | Tb::EndWhile(span)
+         | Tb::Empty(span)
+         | Tb::Break(span)
+         | Tb::Continue(span)
+         | Tb::Verbatim(span)
+         | Tb::EndVerbatim(span)
          | Tb::TagOpenStart(span) 
          | Tb::TagOpenEnd(span) 
          | Tb::TagCloseStart(span) 
          | Tb::TagClose(span) 
+         | Tb::TagSelfClose(span)
+         | Tb::AttrName(span)
+         | Tb::AttrEq(span)
+         | Tb::AttrQuote(span)
+         | Tb::AttrValue(span)
+         | Tb::HtmlCommentOpen(span)
+         | Tb::HtmlCommentClose(span)
+         | Tb::Doctype(span)
+         | Tb::CdataOpen(span)
+         | Tb::CdataClose(span)
          | Tb::EscapedAt(span) 
          | Tb::UnescapedAt(span)
          | Tb::Defered(span) 
          | Tb::OpenParen(span) 
          | Tb::CloseParen(span) 
+         | Tb::StringLiteral(span)
          | Tb::Lt(span) 
          | Tb::Gt(span) 
          | Tb::WhiteSpace(span)
@@ -112,6 +279,12 @@ impl TokenBody {
          | Tb::WhiteSpaceWhole(span)
          | Tb::FilePath(span)       
          | Tb::Newline(span)
+         | Tb::InterpolationOpen(span, _)
+         | Tb::InterpolationClose(span)
+         | Tb::RawInterpolationOpen(span, _)
+         | Tb::RawInterpolationClose(span)
+         | Tb::Expression(span)
+         | Tb::Comment(span)
          => {
             let span_clone = *span;
             span_clone
@@ -150,6 +323,50 @@ impl<'a, F: SpanFormatter> fmt::Debug for TokenBodyFormatWrapper<'a, F> {
       let (start, end) = match self.0 {
          Tb::Include(..)
            => (Some("Include("), Some(")")),
+         Tb::Require(..)
+            => (Some("Require("), Some(")")),
+         Tb::Extends(..)
+            => (Some("Extends("), Some(")")),
+         Tb::Section(..)
+            => (Some("Section("), Some(")")),
+         Tb::EndSection(..)
+            => (Some("EndSection("), Some(")")),
+         Tb::Yield(..)
+            => (Some("Yield("), Some(")")),
+         Tb::If(..)
+            => (Some("If("), Some(")")),
+         Tb::ElseIf(..)
+            => (Some("ElseIf("), Some(")")),
+         Tb::Else(..)
+            => (Some("Else("), Some(")")),
+         Tb::EndIf(..)
+            => (Some("EndIf("), Some(")")),
+         Tb::Unless(..)
+            => (Some("Unless("), Some(")")),
+         Tb::EndUnless(..)
+            => (Some("EndUnless("), Some(")")),
+         Tb::Foreach(..)
+            => (Some("Foreach("), Some(")")),
+         Tb::EndForeach(..)
+            => (Some("EndForeach("), Some(")")),
+         Tb::For(..)
+            => (Some("For("), Some(")")),
+         Tb::EndFor(..)
+            => (Some("EndFor("), Some(")")),
+         Tb::While(..)
+            => (Some("While("), Some(")")),
+         Tb::EndWhile(..)
+            => (Some("EndWhile("), Some(")")),
+         Tb::Empty(..)
+            => (Some("Empty("), Some(")")),
+         Tb::Break(..)
+            => (Some("Break("), Some(")")),
+         Tb::Continue(..)
+            => (Some("Continue("), Some(")")),
+         Tb::Verbatim(..)
+            => (Some("Verbatim("), Some(")")),
+         Tb::EndVerbatim(..)
+            => (Some("EndVerbatim("), Some(")")),
          Tb::TagOpenStart(..) 
             => (Some("TagOpenStart("), Some(")")),
          Tb::TagOpenEnd(..) 
@@ -158,6 +375,26 @@ impl<'a, F: SpanFormatter> fmt::Debug for TokenBodyFormatWrapper<'a, F> {
             => (Some("TagCloseStart("), Some(")")),
          Tb::TagClose(..) 
             => (Some("TagClose("), Some(")")),
+         Tb::TagSelfClose(..)
+            => (Some("TagSelfClose("), Some(")")),
+         Tb::AttrName(..)
+            => (Some("AttrName("), Some(")")),
+         Tb::AttrEq(..)
+            => (Some("AttrEq("), Some(")")),
+         Tb::AttrQuote(..)
+            => (Some("AttrQuote("), Some(")")),
+         Tb::AttrValue(..)
+            => (Some("AttrValue("), Some(")")),
+         Tb::HtmlCommentOpen(..)
+            => (Some("HtmlCommentOpen("), Some(")")),
+         Tb::HtmlCommentClose(..)
+            => (Some("HtmlCommentClose("), Some(")")),
+         Tb::Doctype(..)
+            => (Some("Doctype("), Some(")")),
+         Tb::CdataOpen(..)
+            => (Some("CdataOpen("), Some(")")),
+         Tb::CdataClose(..)
+            => (Some("CdataClose("), Some(")")),
          Tb::EscapedAt(..) 
             => (Some("EscapedAt("), Some(")")),
          Tb::UnescapedAt(..)
@@ -168,6 +405,8 @@ impl<'a, F: SpanFormatter> fmt::Debug for TokenBodyFormatWrapper<'a, F> {
             => (Some("OpenParen("), Some(")")),
          Tb::CloseParen(..) 
             => (Some("CloseParen("), Some(")")),
+         Tb::StringLiteral(..)
+            => (Some("StringLiteral("), Some(")")),
          Tb::Lt(..) 
             => (Some("Lt("), Some(")")),
          Tb::Gt(..) 
@@ -184,6 +423,18 @@ impl<'a, F: SpanFormatter> fmt::Debug for TokenBodyFormatWrapper<'a, F> {
             => (Some("FilePath("), Some(")")),
          Tb::Newline(..) 
             => (Some("Newline("), Some(")")),
+         Tb::InterpolationOpen(..)
+            => (Some("InterpolationOpen("), Some(")")),
+         Tb::InterpolationClose(..)
+            => (Some("InterpolationClose("), Some(")")),
+         Tb::RawInterpolationOpen(..)
+            => (Some("RawInterpolationOpen("), Some(")")),
+         Tb::RawInterpolationClose(..)
+            => (Some("RawInterpolationClose("), Some(")")),
+         Tb::Expression(..)
+            => (Some("Expression("), Some(")")),
+         Tb::Comment(..)
+            => (Some("Comment("), Some(")")),
       };
 
       if let Some(start) = start {
@@ -1,26 +1,23 @@
-//! Deque-like buffer for Token buffering.
+//! Deque for Token buffering.
 //!
-//! TokenBuf provides a simple dequeue-like API over Vec. Various tokenization 
+//! TokenBuf is a growable ring buffer of Tokens. Various tokenization
 //! components require a deque where to store Tokens, because in Iterator only
 //! single Token is returned, while the Tokenizer itself has analyzed code and
 //! generated multiple Tokens. The same applies when Tokenizer returns Error
 //! tokens, it must store them somewhere since Iterator returns only one Token
 //! at a time.
 //!
-//! TokenBuf is inteded to be used for crate internally, since it has some 
-//! limitations that real deque implementations don't.
+//! TokenBuf is inteded to be used for crate internally. Tokens can be pushed
+//! to both ends and popped from the front in any order, i.e. IncludeResolver
+//! pops collected batch from the front and pushes it back transformed.
 //!
-//! It is not allowed to append and popleft Tokens from TokenBuf at the same 
-//! time. Caller either appends items till it's done, or pops items until 
-//! TokenBuf is empty (that's a limitation due to used design, but it is good
-//! enough to satisfy Tokenizer requirements).
-//!
-//! TokenBuf is light-weight compared to reald deque, since it uses only Vec
-//! for data storage.
+//! TokenBuf is light-weight compared to VecDeque in a sense that it never
+//! panics when memory can not be allocated, it returns NoMemory error instead,
+//! see DD-2026-10-16-11.
 //!
 //! When we look at TokenBuf, we imagine that the end is on the right side, thus
-//! when items are appended, they are appended on the right side. And items are
-//! removed from the left side.
+//! when items are pushed back, they are appended on the right side. And items
+//! are removed from the left side.
 
 use crate::{
    token::Token,
@@ -33,18 +30,22 @@ use crate::{
 
 
 
+// Number of slots, that are reserved, when TokenBuf grows for the first time.
+const TOKENBUF_CAP_MIN: usize = 16;
+
+
+
 #[derive(Debug)]
 pub struct TokenBuf {
-   // Number of tokens that are available inside tokenbuf. At the same time this
-   // is how many items from the tokenbuf end has to be consumed before clearing
-   // array empty.
+   // Number of tokens that are available inside tokenbuf.
    num_tokens: usize,
 
-   // This buffer stores tokens temporarily. The idea is that while tokenizer is
-   // consuming text, it can happen that it recognizes multiple tokens in one
-   // go, but iterator interface requires us to return only single item. Thus
-   // non returned items can be stored in buffer. This should make it easier to
-   // write a tokenizer.
+   // Index of the first Token in buf.
+   head: usize,
+
+   // Ring buffer slots. Slots, that do not hold Tokens, are filled with
+   // StateChange, thus Tokens can be moved out without Option or unsafe.
+   // Length of buf is ring capacity, it is changed only when TokenBuf grows.
    buf: Vec<Token>,
 }
 
@@ -52,9 +53,13 @@ pub struct TokenBuf {
 
 impl TokenBuf {
    pub fn new() -> Self {
+      let mut buf = Vec::with_capacity(TOKENBUF_CAP_MIN);
+      buf.resize(TOKENBUF_CAP_MIN, Token::StateChange);
+
       Self {
          num_tokens: 0,
-         buf: Vec::with_capacity(16)
+         head: 0,
+         buf: buf,
       }
    }
 
@@ -68,9 +73,9 @@ impl TokenBuf {
    /// within Token stream.
    ///
    /// If error is returned, caller is responsible to modify returned Token's
-   /// Source.pos_zero accordingly. This i believe is a good performance 
+   /// Source.pos_zero accordingly. This i believe is a good performance
    /// tradeoff because errors are expected to be from rare to none, and we do
-   /// not want to pay with stack allocations in each call just to store 
+   /// not want to pay with stack allocations in each call just to store
    /// pos_zero value for those rare ocassions.
    ///
    /// # Arguments
@@ -79,12 +84,8 @@ impl TokenBuf {
    ///
    /// # Panics
    ///
-   /// This function does not panic, unless there is a Bug in code.
-   ///
-   /// By design Vec.push can panic if there is not enough memory available when
-   /// capacity has to be increased. This function handles that case by calling
-   /// try_reserve, which does not panic, thus Vec capacity should always be 
-   /// enough to store Token item and not panic.
+   /// This function does not panic. Capacity is increased with try_reserve,
+   /// which does not panic, when there is not enough memory available.
    ///
    /// # Return
    ///
@@ -94,58 +95,17 @@ impl TokenBuf {
    /// * 'Err(Token)' - error, contains Token that can be forwarded to consumer
    ///   to inform about error that has occured.
    ///
-   pub fn append(&mut self, token: Token) -> Result<(), Token> {
-      let tb = &mut self.buf;
-
-      // Ensure that there is enough memory in Vec. This is done because push 
-      // will panic if there is not enough memory available, but we do not want
-      // to panic in such cases. There is an experimental API function
-      // push_within_capacity available, but i do not want to use experimental 
-      // API either.
-      let cap = tb.capacity();
-      let len = tb.len();
-      if cap < len + 1 {
-         if let Err(..) = tb.try_reserve(16) {
-            return Err(Token::Fatal(ParseError::NoMemory(Source {
-               pos_zero: 0,
-               component: Component::TokenBuf,
-               line: line!(),
-               code: 3,
-            })));
-         }
+   pub fn push_back(&mut self, token: Token) -> Result<(), Token> {
+      if let Err(etoken) = self.grow() {
+         return Err(etoken);
       }
 
       #[cfg(feature = "dbg_tokenbuf_verbose")] {
-         println!("Tokenbuf push: {:?}", token);
+         println!("Tokenbuf push back: {:?}", token);
       }
 
-      // This feature is mostly intended to be used while developing to detect
-      // some Tokenizer's state problems.
-      //
-      // In general, the idea is simple - Tokenizer should do single action
-      // untill it is complete (regarding TokenBuf); i.e. if Tokenizer is 
-      // pushing tokens into Tokenbuf, then it does only push action, if 
-      // Tokenizer is consuming items from TokenBuf, then it consumes till 
-      // TokenBuf is empty. It is not allowed to insert 5 items, consume 2, then
-      // insert 1, and then consume 4 items. All-in or all-out, each action can
-      // be iterated.
-      //
-      // This constraint exists, because i don't want to use real dequeue. It 
-      // seems too heavy of a data structure for this task. We just need to 
-      // buffer some tokens that were parsed and then return them through 
-      // iterator till buffer is empty.
-      #[cfg(feature = "tokenbuf_push_guard")] {
-         if len != self.num_tokens {
-            return Err(Token::Fatal(ParseError::InternalError(Source {
-               pos_zero: 0,
-               component: Component::TokenBuf,
-               line: line!(),
-               code: 3,
-            })));
-         }
-      }
-
-      self.buf.push(token);
+      let idx = self.slot(self.num_tokens);
+      self.buf[idx] = token;
       self.num_tokens += 1;
 
       Ok(())
@@ -153,108 +113,69 @@ impl TokenBuf {
 
 
 
-   /// Removes first Token out of TokenBuf and returns it.
-   ///
-   /// It is allowed to use TokenBuf in all-in/all-out manner only.
-   ///
-   /// # Return
-   ///
-   /// `Ok(None)` - if TokenBuf is empty, there is nothing to be returned.
-   ///
-   /// `Ok(Token)` - when TokenBuf is not empty, a Token is returned.
-   ///
-   /// `Err(Token)` - this should never happen if code works as intended, but in
-   ///    case there is a bug in code, an error is returned containing Token, 
-   ///    that envelops Source for error.
-   ///
-   pub fn popleft(&mut self) -> Result<Option<Token>, Token> {
-      if self.num_tokens < 1 {
-         return Ok(None);
+   /// Prepend Token to the start of this TokenBuf, thus it is the next one
+   /// returned by pop_front. Errors are the same as for push_back.
+   pub fn push_front(&mut self, token: Token) -> Result<(), Token> {
+      if let Err(etoken) = self.grow() {
+         return Err(etoken);
       }
 
-      let num_tokens = self.num_tokens;
-      let num_in_buf = self.buf.len();
-
-      // It is necessary to guard against access outside Vec buffer. We don't
-      // want to panic due to such an error.
-      if num_in_buf < num_tokens {
-         // Unfortunateley we must alter tokenbuf state to avoid infinite loop.
-         self.num_tokens = 0;
-         self.buf.clear();
-
-         return Err(Token::Fatal(ParseError::InternalError(Source {
-            pos_zero: 0,
-            component: Component::TokenBuf,
-            line: line!(),
-            code: 1,
-         })));
+      #[cfg(feature = "dbg_tokenbuf_verbose")] {
+         println!("Tokenbuf push front: {:?}", token);
       }
 
-      let idx_item = num_in_buf - num_tokens;
-      self.num_tokens -= 1;
+      self.head = self.slot(self.buf.len() - 1);
+      self.buf[self.head] = token;
+      self.num_tokens += 1;
 
-      #[cfg(feature = "dbg_tokenbuf_verbose")] {
-         println!("Tokenbuf: return item at index: {}", idx_item);
-      }
+      Ok(())
+   }
 
-      let num_tokens = self.num_tokens;
 
-      let tok_ref = self.buf.get(idx_item);
-      let token = if let Some(tok_ref) = tok_ref {
-         let item = (*tok_ref).clone();
-         #[cfg(feature = "dbg_tokenbuf_verbose")] {
-            println!("Tokenbuf: return item at item: {:?}", item);
-         }
-         item
+
+   /// Removes first Token out of TokenBuf and returns it, None if TokenBuf is
+   /// empty.
+   pub fn pop_front(&mut self) -> Option<Token> {
+      if self.num_tokens < 1 {
+         return None;
       }
-      else {
-         // This is really bad case; bug in code. The only way i see this
-         // happening is that num_tokens is out of sync with tokenbuf.len(), but
-         // never the less, we must handle that gracefully.
-
-         // Unfortunateley we must alter tokenbuf state, because 
-         // TokenBuf.popleft is allowed to be called even if Tokenizer is in
-         // Failed state. I don't expect this to ever happen, but in case it
-         // does happen, we will add extra debugging at that time.
-         self.num_tokens = 0;
-         self.buf.clear();
-
-         return Err(Token::Fatal(ParseError::InternalError(Source {
-            pos_zero: 0,
-            component: Component::TokenBuf,
-            line: line!(),
-            code: 2,
-         })));
-      };
+
+      let token = std::mem::replace(&mut self.buf[self.head], Token::StateChange);
+      self.head = self.slot(1);
+      self.num_tokens -= 1;
 
       #[cfg(feature = "dbg_tokenbuf_verbose")] {
-         println!("Tokenbuf: array num items: {}, self.num_tokens: {}",
-            num_in_buf, num_tokens
+         println!("Tokenbuf: pop front: {:?}, num_tokens: {}", token,
+            self.num_tokens
          );
       }
 
-      // This was the last item that we cloned out, so we can clear Vec.
-      if num_tokens < 1 {
-         #[cfg(feature = "dbg_tokenbuf_verbose")] {
-            println!("self.num_tokens: {}, must CLEAR BUFFER", self.num_tokens);
-         }
+      Some(token)
+   }
+
+
 
-         self.buf.clear();
+   /// Returns n-th Token from the front without removing it; peek(0) is the
+   /// one, that pop_front returns next. None if there are not that many Tokens.
+   pub fn peek(&self, n: usize) -> Option<&Token> {
+      if n >= self.num_tokens {
+         return None;
       }
 
-      Ok(Some(token))
+      Some(&self.buf[self.slot(n)])
    }
 
 
 
-   /// Returns the number of slots reserved in buffer for Tokens.
-   ///
-   /// Since TokenBuf is used like dequeue, length of Vec does not necessarily
-   /// represent the number of Tokens stored. It can be the number or Tokens
-   /// stored available for reading from TokenBuf, but it can be more than
-   /// available Tokens in cases when some Tokens are already consumed.
-   pub fn buf_len(&self) -> usize {
-      self.buf.len()
+   /// Removes all Tokens out of TokenBuf, allocated capacity is kept.
+   pub fn clear(&mut self) {
+      while self.num_tokens > 0 {
+         self.buf[self.head] = Token::StateChange;
+         self.head = self.slot(1);
+         self.num_tokens -= 1;
+      }
+
+      self.head = 0;
    }
 
 
@@ -263,8 +184,52 @@ impl TokenBuf {
    pub fn num_tokens(&self) -> usize {
       self.num_tokens
    }
+
+
+
+   // Returns buf index for n-th slot from head.
+   #[inline(always)]
+   fn slot(&self, n: usize) -> usize {
+      (self.head + n) % self.buf.len()
+   }
+
+
+
+   // Make room for one more Token. When ring is full, it is unwrapped, so that
+   // head is at index 0, and its size is doubled.
+   #[inline(always)]
+   fn grow(&mut self) -> Result<(), Token> {
+      let len = self.buf.len();
+      if self.num_tokens < len {
+         return Ok(());
+      }
+
+      // Ensure that there is enough memory in Vec. This is done because resize
+      // will panic if there is not enough memory available, but we do not want
+      // to panic in such cases.
+      let len_new = (len * 2).max(TOKENBUF_CAP_MIN);
+      if let Err(..) = self.buf.try_reserve(len_new - len) {
+         return Err(Token::Fatal(ParseError::NoMemory(Source {
+            pos_zero: 0,
+            component: Component::TokenBuf,
+            line: line!(),
+            code: 3,
+         })));
+      }
+
+      self.buf.rotate_left(self.head);
+      self.buf.resize(len_new, Token::StateChange);
+      self.head = 0;
+
+      Ok(())
+   }
 }
 
 
 
+#[cfg(test)]
+mod test;
+
+
+
 // ================== EOF: do not write below this ============================
@@ -0,0 +1,113 @@
+use crate::{
+   tokenbuf::TokenBuf,
+   token::Token,
+   tokenbody::TokenBody,
+   span::Span,
+};
+
+
+
+fn token_at(pos_zero: usize) -> Token {
+   Token::Real(TokenBody::Defered(Span {
+      index: 0,
+      length: 1,
+      pos_line: pos_zero,
+      pos_region: pos_zero,
+      pos_zero: pos_zero,
+      line: 0,
+   }))
+}
+
+
+
+fn pos_zero_of(token: Option<&Token>) -> Option<usize> {
+   token.and_then(|token| token.span_clone()).map(|span| span.pos_zero)
+}
+
+
+
+// This tests interleaved push_back and pop_front, so that head wraps around
+// the end of ring several times, and then ring grows while it is wrapped.
+// Tokens must be returned in the same order they were pushed.
+// cargo test tokenbuf::test::tokenbuf_test_wrap_around -- --nocapture
+#[test]
+fn tokenbuf_test_wrap_around() {
+   let mut tb = TokenBuf::new();
+   let mut pos_push = 0;
+   let mut pos_pop = 0;
+
+   // Keep 10 Tokens in buffer, while 100 go through it.
+   for _ in 0..100 {
+      if let Err(etoken) = tb.push_back(token_at(pos_push)) {
+         panic!("Could not push token into tokenbuf: {:?}", etoken);
+      }
+      pos_push += 1;
+
+      if tb.num_tokens() > 10 {
+         assert_eq!(pos_zero_of(tb.pop_front().as_ref()), Some(pos_pop));
+         pos_pop += 1;
+      }
+   }
+
+   // Now grow wrapped ring.
+   for _ in 0..50 {
+      if let Err(etoken) = tb.push_back(token_at(pos_push)) {
+         panic!("Could not push token into tokenbuf: {:?}", etoken);
+      }
+      pos_push += 1;
+   }
+
+   assert_eq!(tb.num_tokens(), pos_push - pos_pop);
+   assert_eq!(pos_zero_of(tb.peek(0)), Some(pos_pop));
+   assert_eq!(pos_zero_of(tb.peek(59)), Some(pos_push - 1));
+   assert_eq!(tb.peek(60), None);
+
+   while let Some(token) = tb.pop_front() {
+      assert_eq!(pos_zero_of(Some(&token)), Some(pos_pop));
+      pos_pop += 1;
+   }
+
+   assert_eq!(pos_pop, pos_push);
+   assert_eq!(tb.num_tokens(), 0);
+}
+
+
+
+// This tests push_front, Tokens pushed to the front are returned first, in
+// reverse order they were pushed, also when ring has to grow.
+// cargo test tokenbuf::test::tokenbuf_test_push_front -- --nocapture
+#[test]
+fn tokenbuf_test_push_front() {
+   let mut tb = TokenBuf::new();
+
+   for pos_zero in 20..30 {
+      if let Err(etoken) = tb.push_back(token_at(pos_zero)) {
+         panic!("Could not push token into tokenbuf: {:?}", etoken);
+      }
+   }
+
+   for pos_zero in (0..20).rev() {
+      if let Err(etoken) = tb.push_front(token_at(pos_zero)) {
+         panic!("Could not push token into tokenbuf: {:?}", etoken);
+      }
+   }
+
+   assert_eq!(pos_zero_of(tb.peek(0)), Some(0));
+
+   for pos_zero in 0..30 {
+      assert_eq!(pos_zero_of(tb.pop_front().as_ref()), Some(pos_zero));
+   }
+
+   assert_eq!(tb.pop_front(), None);
+
+   // Buffer must be usable after clear.
+   for pos_zero in 0..5 {
+      if let Err(etoken) = tb.push_front(token_at(pos_zero)) {
+         panic!("Could not push token into tokenbuf: {:?}", etoken);
+      }
+   }
+
+   tb.clear();
+   assert_eq!(tb.num_tokens(), 0);
+   assert_eq!(tb.peek(0), None);
+}
@@ -1,10 +1,13 @@
+use std::borrow::Cow;
+
 use crate::{
    token::Token,
-   tokenbody::TokenBody,
+   tokenbody::{TokenBody, TextContext},
    tokenbuf::TokenBuf,
-   span::Span,
+   span::{Span, SpanColumns, span_columns},
    parse_error::{
       ParseError,
+      Suggestion,
       Source,
       Component,
    }
@@ -13,11 +16,18 @@ use crate::{
 mod formatter;
 mod iterator;
 mod ident;
+mod tag;
+mod syntax;
+mod scan;
+mod lookahead;
 
-use ident::{Ident, ident_match};
+pub use syntax::Syntax;
+pub use lookahead::Checkpoint;
+use ident::{Ident, IdentArgs, ident_match, ident_scan, ident_suggest};
+use tag::{Tag, tag_match, tag_raw_text};
 
 // Tokenizer states.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TokenizerState {
    /// This is the initial state for Tokenizer. In this state user is not
    /// allowed to invoke iterator::next, since there is no source to tokenize.
@@ -32,6 +42,48 @@ pub enum TokenizerState {
    /// parenthesis has been tokenized, i.e. "@include(", "@if(", etc.
    ExpectInstructionClose,
 
+   /// Tokenizer switches to this state when TagOpenStart has been tokenized,
+   /// i.e. "<div". Tokenizer stays in this state untill ">" or "/>" is
+   /// matched; everything in between (attributes) is returned as Defered.
+   ExpectTagOpenEnd,
+
+   /// Tokenizer switches to this state when TagCloseStart has been tokenized,
+   /// i.e. "</div" that was not immediately followed by ">".
+   ExpectTagCloseEnd,
+
+   /// Tokenizer switches to this state when InterpolationOpen "{{" has been
+   /// tokenized. Everything till "}}" is returned as Expression.
+   ExpectInterpolationClose,
+
+   /// Tokenizer switches to this state when RawInterpolationOpen "{!!" has
+   /// been tokenized. Everything till "!!}" is returned as Expression.
+   ExpectRawInterpolationClose,
+
+   /// Tokenizer switches to this state when template comment "{{--" has been
+   /// matched. Everything till "--}}" is returned as Comment.
+   ExpectCommentClose,
+
+   /// Tokenizer switches to this state when Verbatim "@verbatim" has been
+   /// tokenized. Everything till "@endverbatim" is returned as Defered.
+   ExpectVerbatimEnd,
+
+   /// Tokenizer switches to this state when TagOpenEnd has been tokenized for
+   /// raw-text element, i.e. "<script>", "<style>", "<textarea>" or
+   /// "<title>". Tags are not tokenized till matching closing tag, thus "<"
+   /// within JavaScript is returned as Defered. Instructions and
+   /// interpolations are tokenized, interpolation gets element's TextContext.
+   ExpectRawTextEnd,
+
+   /// Tokenizer switches to this state when HtmlCommentOpen "<!--" has been
+   /// tokenized. Everything till "-->" is returned as Defered, except
+   /// instructions and interpolations. See DD-2026-10-16-01.
+   ExpectHtmlCommentClose,
+
+   /// Tokenizer switches to this state when CdataOpen "<![CDATA[" has been
+   /// tokenized. Content is tokenized the same way as for HTML comment, till
+   /// "]]>".
+   ExpectCdataClose,
+
    /// This state is active when Tokenizer has got into unrecoverable
    /// tokenization error. This can happen due to various reasons, like, bug in
    /// code, bad input, etc. Once Tokenizer is in this sate it will not recover
@@ -74,6 +126,43 @@ struct StateSnap {
 
 
 
+// What is expected next when tokenizing attributes within open tag.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum TagAttr {
+   // Attribute name, i.e. at tag start or after whitespace that follows
+   // value.
+   Name,
+
+   // Attribute value right after "=", whitespace is allowed before it.
+   Value,
+
+   // Within unquoted attribute value, whitespace ends it.
+   Unquoted,
+}
+
+
+
+// Line ending that is used in template source region, see DD-2026-10-16-02.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NewlineStyle {
+   // There was no Newline in region yet.
+   Unknown,
+
+   // "\n"
+   Lf,
+
+   // "\r\n"
+   CrLf,
+
+   // Lone "\r"
+   Cr,
+
+   // Different line endings were found and MixedNewline warning was given.
+   Mixed,
+}
+
+
+
 // Each time when some source is pushed in region Vec, we store some information
 // that is useful to make errors/warnings more verbose.
 // We will need to read this only in Parser code, so for now ignore warnings.
@@ -96,6 +185,29 @@ struct SrcRegionMeta {
    // It can be None, when contents are not read from file, for example when
    // testing or generating template string for parsing.
    filename: Option<String>,
+
+   // Position till which region is validated as UTF-8. For open region it
+   // stops before incomplete character at the end of appended bytes. Once
+   // invalid byte is found, this is usize::MAX and region is not validated
+   // any more.
+   pos_utf8: usize,
+
+   // Position of the first invalid UTF-8 byte in region, that is not reported
+   // yet, usize::MAX if there is none.
+   pos_utf8_invalid: usize,
+
+   // True if UTF-8 BOM was removed from region start.
+   bom: bool,
+
+   // Line ending of the first Newline returned from region.
+   newline: NewlineStyle,
+
+   // pos_zero right after the last Token of region and regions included from
+   // it. It is usize::MAX till region is tokenized completely.
+   pos_zero_end: usize,
+
+   // True if region bytes are released, see region_release.
+   released: bool,
 }
 
 
@@ -103,8 +215,13 @@ struct SrcRegionMeta {
 /// Tokenizer struct stores internal state for Tokenizer. Each time a new byte
 /// is read, it increases pos_*, line values, once Token is recognized, those
 /// values are copied into Span token that is wrapped with returned Token.
+///
+/// Lifetime 'src is the lifetime of sources pushed with src_push_borrowed,
+/// they are tokenized in place without copying. Sources pushed with src_push
+/// are owned by Tokenizer, thus Tokenizer<'static> can take both owned and
+/// 'static sources, i.e. include_bytes!.
 #[derive(Debug)]
-pub struct Tokenizer {
+pub struct Tokenizer<'src> {
    /// This is the index for current active region string. This will be cloned
    /// into Span when token is recognized.
    index: usize,
@@ -120,7 +237,8 @@ pub struct Tokenizer {
    /// This is a line position in current region. Copied into Span.
    pos_line: usize,
 
-   /// This stores current max allowed position in region.
+   /// This stores current max allowed position in region. For region that is
+   /// open for src_append, this is pos_max_open.
    pos_max: usize,
 
    /// This is a line number in current region. Copied into Span.
@@ -136,7 +254,20 @@ pub struct Tokenizer {
    // region does not work exactly as a stack; it is append only array, where
    // new item is pushed on each @include or similar directive, but pop actually
    // restores current state to region from which @include was called.
-   region: Vec<Vec<u8>>,
+   //
+   // Region is borrowed, if it was pushed with src_push_borrowed, otherwise it
+   // is owned. Region that is open for src_append is always owned.
+   region: Vec<Cow<'src, [u8]>>,
+
+   // Index for region that is open for src_append, see src_push_open. It is
+   // None when all pushed regions are complete. Only one region can be open
+   // at a time.
+   region_open: Option<usize>,
+
+   // Position in open region till which it can be tokenized, that is right
+   // after the last appended newline. Bytes after it can be a part of token,
+   // that straddles appended chunks, i.e. "@incl" + "ude(".
+   pos_max_open: usize,
 
    /// This buffer stores tokens temporarily. The idea is that while tokenizer is
    /// consuming text, it can happen that it recognizes multiple tokens in one
@@ -167,6 +298,265 @@ pub struct Tokenizer {
    // pos_zero for previously handled instruction. At the moment the use-case 
    // for this is to allow generating error tokens regarding instructions.
    pos_zero_prev_instr: usize,
+
+   // State that was active when instruction was matched. Instructions can be
+   // used in text as well as within tags, thus when instruction is closed,
+   // Tokenizer must return to the state it was in before.
+   state_prev_instr: TokenizerState,
+
+   // pos_zero for previously matched TagOpenStart/TagCloseStart. This is used
+   // to generate error tokens for tags that are never closed.
+   pos_zero_prev_tag: usize,
+
+   // State that was active when interpolation was opened. Interpolation can
+   // be used in text and within tags (attribute values), thus when it is
+   // closed, Tokenizer must return to the state it was in before.
+   state_prev_interp: TokenizerState,
+
+   // pos_zero for previously matched InterpolationOpen/RawInterpolationOpen.
+   // This is used to generate error tokens for interpolations that are never
+   // closed.
+   pos_zero_prev_interp: usize,
+
+   // State that was active when template comment was opened. Comments can be
+   // used in text and within tags.
+   state_prev_comment: TokenizerState,
+
+   // pos_zero for previously matched comment start "{{--". This is used to
+   // generate error tokens for comments that are never closed.
+   pos_zero_prev_comment: usize,
+
+   // State that was active when "@verbatim" was matched.
+   state_prev_verbatim: TokenizerState,
+
+   // pos_zero for previously matched "@verbatim". This is used to generate
+   // error tokens for verbatim blocks that are never closed.
+   pos_zero_prev_verbatim: usize,
+
+   // When tokenizing tag contents, this stores the quote byte (0x22 or 0x27)
+   // if Tokenizer is within quoted attribute value, otherwise 0. This is
+   // necessary, because quoted value can span over multiple lines, and each
+   // Newline is returned as a separate Token.
+   tag_quote: u8,
+
+   // When tokenizing open tag contents, this tells what is expected next,
+   // attribute name or value. Value can follow only after "=".
+   tag_attr: TagAttr,
+
+   // TextContext for element of last TagOpenStart. It is TextContext::Text if
+   // element is not a raw-text element. When ExpectRawTextEnd is active, it
+   // tells which closing tag ends raw text.
+   raw_text: TextContext,
+
+   // State that was active when HTML comment or CDATA section was opened.
+   state_prev_markup: TokenizerState,
+
+   // pos_zero for previously matched HtmlCommentOpen/CdataOpen. This is used
+   // to generate error tokens for HTML comments and CDATA sections that are
+   // never closed.
+   pos_zero_prev_markup: usize,
+
+   // Tab width for span_columns. It does not affect tokenization.
+   tab_width: usize,
+
+   // Position of the first invalid UTF-8 byte in current region, that is not
+   // reported yet, usize::MAX if there is none. This is a copy of
+   // SrcRegionMeta.pos_utf8_invalid for current region, so that
+   // return_tokenized does not have to look it up for each Token.
+   pos_utf8_invalid: usize,
+
+   // InvalidUtf8 error, that must be returned by Iterator right after Token,
+   // that envelops invalid byte.
+   utf8_error: Option<ParseError>,
+
+   // MixedNewline warning, that must be returned by Iterator right after
+   // Newline.
+   newline_warning: Option<ParseError>,
+
+   // Instruction sigil and interpolation delimiters.
+   syntax: Syntax,
+
+   // Number of Error and Warning Tokens, that Iterator is allowed to return.
+   // When the next one is tokenized, Tokenizer fails, see error_limit_set.
+   error_limit: usize,
+   warning_limit: usize,
+
+   // Number of Error and Warning Tokens returned by Iterator so far.
+   cnt_error: usize,
+   cnt_warning: usize,
+
+   // Region index and position right after Token with trim marker, i.e.
+   // "-}}" or "@-endif". When text is tokenized from there, whitespace till
+   // the end of line and newline are returned as Phantom.
+   trim_right: Option<(usize, usize)>,
+
+   // Instruction, that is being tokenized, has trim marker, i.e. "@-if(".
+   // Whitespace after its closing parenthesis is trimmed.
+   trim_instr: bool,
+
+   // Tokens, that were tokenized ahead by peek, or returned by Iterator while
+   // there is a checkpoint, thus they can be returned again after rewind.
+   // Each of them has already passed return_tokenized.
+   lookahead: Vec<Token>,
+
+   // Index in lookahead of the Token, that Iterator returns next.
+   lookahead_pos: usize,
+
+   // Number of Tokens returned by Iterator before lookahead[0].
+   lookahead_base: usize,
+
+   // Ids of checkpoints, that are not released yet, the innermost one is the
+   // last. While there is any, returned Tokens are kept in lookahead.
+   checkpoint_live: Vec<usize>,
+
+   // Id for the next checkpoint. It is not reset, thus released checkpoint
+   // can not be taken for a live one made later at the same position.
+   checkpoint_id_next: usize,
+
+   // Number of resets, see reset. Checkpoints from previous generations are
+   // refused by rewind.
+   generation: usize,
+
+   // pos_zero at which current generation starts. Spans with lower pos_zero
+   // are from previous generations.
+   pos_zero_generation: usize,
+}
+
+
+
+// Push Defered token for bytes between pos_token_start and pos (if there are
+// any) and Token with given body, that starts at pos, into tokenbuf.
+//
+// This function exists for tokenize_instruction_args, where Tokens can not be
+// returned right away, since it is not known if instruction will be closed.
+// All positions are relative to region being tokenized, pos_region and
+// pos_zero are Tokenizer's current positions.
+//
+// # Return
+//
+// * Ok(()) - if both tokens were pushed.
+// * Err(Token) - returns token, that describes error.
+#[inline(always)]
+fn tokenizer_args_push(tokenbuf: &mut TokenBuf, index: usize, pos_region: usize,
+   pos_zero: usize, pos_line_start: usize, pos_token_start: usize, pos: usize,
+   line: usize, body: fn(Span) -> TokenBody, length: usize,
+) -> Result<(), Token> {
+   let pos_in_line = pos_token_start - pos_line_start;
+   let len_defered = pos - pos_token_start;
+   let len_prev_token = pos_token_start - pos_region;
+
+   if len_defered > 0 {
+      if let Err(token) = tokenbuf.push_back(Token::Real(
+         TokenBody::Defered(Span {
+            index: index,
+            pos_region: pos_token_start,
+            pos_zero: pos_zero + len_prev_token,
+            pos_line: pos_in_line,
+            line: line,
+            length: len_defered,
+         })
+      )){
+         return Err(token);
+      };
+   }
+
+   tokenbuf.push_back(Token::Real(body(Span {
+      index: index,
+      pos_region: pos,
+      pos_zero: pos_zero + len_prev_token + len_defered,
+      pos_line: pos_in_line + len_defered,
+      line: line,
+      length: length,
+   })))
+}
+
+
+
+// Returns length of newline that starts at given position: 2 for "\r\n" and 1
+// for "\n" or lone "\r", see DD-2026-10-16-02. Caller must ensure that there
+// is "\n" or "\r" at given position.
+#[inline(always)]
+fn newline_len(src: &[u8], pos: usize) -> usize {
+   if src[pos] == 0x0D /* \r */ && pos + 1 < src.len() && src[pos + 1] == 0x0A {
+      2
+   }
+   else {
+      1
+   }
+}
+
+
+
+// Returns true if given delimiter starts at given position. Delimiters are
+// short, thus bytes are compared in place, without calling memcmp.
+#[inline(always)]
+fn delimiter_match(src: &[u8], pos: usize, delimiter: &[u8]) -> bool {
+   if pos + delimiter.len() > src.len() {
+      return false;
+   }
+
+   let mut idx = 0;
+   while idx < delimiter.len() {
+      if src[pos + idx] != delimiter[idx] {
+         return false;
+      }
+      idx += 1;
+   }
+
+   true
+}
+
+
+
+// Returns position where instruction name starts for sigil at pos_at. Sigil
+// can be followed by trim marker, i.e. "@-if(", see DD-2026-10-16-07. "-"
+// that is not followed by a letter is not a marker, i.e. "user@-x".
+#[inline(always)]
+fn ident_start(src: &[u8], pos_at: usize) -> usize {
+   if pos_at + 2 < src.len()
+   && src[pos_at + 1] == 0x2D /* - */
+   && src[pos_at + 2].is_ascii_alphabetic()
+   {
+      pos_at + 2
+   }
+   else {
+      pos_at + 1
+   }
+}
+
+
+
+// Returns true if sigil at pos_at follows a letter or digit, i.e. in e-mail
+// address "info@empty.org". Such sigil does not start instruction without
+// arguments and is returned as UnescapedAt without any warning.
+#[inline(always)]
+fn sigil_in_word(src: &[u8], pos_at: usize) -> bool {
+   pos_at > 0 && src[pos_at - 1].is_ascii_alphanumeric()
+}
+
+
+
+// Returns 1 if trim marker is at given position, i.e. right after "{{",
+// otherwise 0. See DD-2026-10-16-07.
+#[inline(always)]
+fn trim_marker_len(src: &[u8], pos: usize) -> usize {
+   match src.get(pos) {
+      Some(0x2D /* - */) => 1,
+      _ => 0,
+   }
+}
+
+
+
+// Returns true if byte at given position is the last byte of newline, i.e.
+// "\n" or lone "\r". Line number changes after such byte.
+#[inline(always)]
+fn newline_last(src: &[u8], pos: usize) -> bool {
+   match src[pos] {
+      0x0A /* newline */ => true,
+      0x0D /* \r */ => pos + 1 >= src.len() || src[pos + 1] != 0x0A,
+      _ => false,
+   }
 }
 
 
@@ -175,8 +565,9 @@ pub struct Tokenizer {
 // results into tokenbuf.
 //
 // Function assumes that everything in provided region is whitespace till the
-// first found 0x0A byte. Function does not analyze if provided region contains
-// only whitespace characters till newline byte; it is callers responsibility.
+// first found newline, that is "\n", "\r\n" or lone "\r". Function does not
+// analyze if provided region contains only whitespace characters till newline;
+// it is callers responsibility.
 //
 // If there is no newline character found, function does not tokenize anything.
 //
@@ -207,8 +598,9 @@ fn tokenizer_line_tokenize(tokenbuf: &mut TokenBuf, index: usize, src: &[u8],
 
    while _pos < pos_end {
       let byte = src[_pos];
-      if let 0x0A = byte {
+      if let 0x0A /* newline */ | 0x0D /* \r */ = byte {
          let len_wsp = _pos - _pos_prev;
+         let len_newline = newline_len(src, _pos);
 
          if len_wsp > 0 {
             let wsp_token = if *pos_line_base == 0 {
@@ -225,7 +617,7 @@ fn tokenizer_line_tokenize(tokenbuf: &mut TokenBuf, index: usize, src: &[u8],
             else {
                // If there is some "text" before, this is a trailing whitespace
                // no matter what position. Whitespaces between text would not
-               // match 0x0A byte at this point.
+               // match newline at this point.
 
                Token::Real(TokenBody::WhiteSpaceTr(Span {
                      index: index,
@@ -237,19 +629,19 @@ fn tokenizer_line_tokenize(tokenbuf: &mut TokenBuf, index: usize, src: &[u8],
                }))
             };
 
-            if let Err(token) = tokenbuf.append(wsp_token) {
+            if let Err(token) = tokenbuf.push_back(wsp_token) {
                return Some(token);
             }
          }
 
-         if let Err(token) = tokenbuf.append(Token::Real(
+         if let Err(token) = tokenbuf.push_back(Token::Real(
             TokenBody::Newline(Span {
                index: index,
                pos_region: _pos_prev + len_wsp,
                pos_zero: pos_zero_base + *parsed_wsp + len_wsp,
                pos_line: *pos_line_base + len_wsp,
                line: *line,
-               length: 1,
+               length: len_newline,
             })
          )) {
             return Some(token);
@@ -260,8 +652,8 @@ fn tokenizer_line_tokenize(tokenbuf: &mut TokenBuf, index: usize, src: &[u8],
          // as long as the state from the outside looks correct.
          // This saves us some processing power.
 
-         _pos += 1;
-         *parsed_wsp += len_wsp + 1;
+         _pos += len_newline;
+         *parsed_wsp += len_wsp + len_newline;
          *pos_prev = _pos;
          *line += 1;
          *pos_line_base = 0;
@@ -310,10 +702,6 @@ fn tokenizer_line_tokenize(tokenbuf: &mut TokenBuf, index: usize, src: &[u8],
 // * `len_region` - length in bytes for whitespace region. It must include last
 //       newline byte as well.
 //
-// # TODO
-//
-// * Implement "\r\n" recognition as a newline. Now we have only "\n".
-//
 #[inline(always)]
 fn tokenizer_whitespace_tokenize(tokenbuf: &mut TokenBuf, index: usize,
    src: &[u8], pos_zero: usize, pos_region: usize, len_region: usize,
@@ -372,7 +760,7 @@ fn tokenizer_whitespace_tokenize(tokenbuf: &mut TokenBuf, index: usize,
       }
       ;
 
-      if let Err(token) = tokenbuf.append(wsp_token) {
+      if let Err(token) = tokenbuf.push_back(wsp_token) {
          return Some(token);
       }
    }
@@ -382,7 +770,7 @@ fn tokenizer_whitespace_tokenize(tokenbuf: &mut TokenBuf, index: usize,
 
 
 
-impl Tokenizer {
+impl<'src> Tokenizer<'src> {
    pub fn new() -> Self {
       Self {
          state: TokenizerState::ExpectInput,
@@ -394,19 +782,144 @@ impl Tokenizer {
          line: 0,
          tokenbuf: TokenBuf::new(),
          region: Vec::with_capacity(8),
+         region_open: None,
+         pos_max_open: 0,
          cnt_openparen: 0,
          cnt_closeparen: 0,
          region_meta: Vec::with_capacity(8),
          state_snap: Vec::with_capacity(8),
          parse_error_prev: ParseError::None,
          pos_zero_prev_instr: 0,
+         state_prev_instr: TokenizerState::ExpectDefered,
+         pos_zero_prev_tag: 0,
+         state_prev_interp: TokenizerState::ExpectDefered,
+         pos_zero_prev_interp: 0,
+         state_prev_comment: TokenizerState::ExpectDefered,
+         pos_zero_prev_comment: 0,
+         state_prev_verbatim: TokenizerState::ExpectDefered,
+         pos_zero_prev_verbatim: 0,
+         tag_quote: 0,
+         tag_attr: TagAttr::Name,
+         raw_text: TextContext::Text,
+         state_prev_markup: TokenizerState::ExpectDefered,
+         pos_zero_prev_markup: 0,
+         tab_width: 4,
+         pos_utf8_invalid: usize::MAX,
+         utf8_error: None,
+         newline_warning: None,
+         syntax: Syntax::new(),
+         error_limit: usize::MAX,
+         warning_limit: usize::MAX,
+         cnt_error: 0,
+         cnt_warning: 0,
+         trim_right: None,
+         trim_instr: false,
+         lookahead: Vec::new(),
+         lookahead_pos: 0,
+         lookahead_base: 0,
+         checkpoint_live: Vec::new(),
+         checkpoint_id_next: 0,
+         generation: 0,
+         pos_zero_generation: 0,
       }
    }
 
+   /// Create Tokenizer, that uses given instruction sigil and interpolation
+   /// delimiters instead of default ones. Syntax is validated when it is
+   /// built, see Syntax::custom.
+   pub fn with_syntax(syntax: Syntax) -> Self {
+      let mut tokenizer = Self::new();
+      tokenizer.syntax = syntax;
+      tokenizer
+   }
+
    pub fn state_set(&mut self, state: TokenizerState) {
       self.state = state;
    }
 
+   /// Set tab width, that is used to calculate display width in span_columns.
+   /// Default is 4.
+   pub fn tab_width_set(&mut self, tab_width: usize) {
+      self.tab_width = tab_width;
+   }
+
+   /// Set how many Error Tokens Iterator is allowed to return. When one more
+   /// Error is tokenized, Fatal ErrorLimit is returned instead and Tokenizer
+   /// fails. Default is usize::MAX, that is no limit.
+   pub fn error_limit_set(&mut self, error_limit: usize) {
+      self.error_limit = error_limit;
+   }
+
+   /// Set how many Warning Tokens Iterator is allowed to return, see
+   /// error_limit_set. Default is usize::MAX, that is no limit.
+   pub fn warning_limit_set(&mut self, warning_limit: usize) {
+      self.warning_limit = warning_limit;
+   }
+
+   /// Reset Tokenizer to ExpectInput state, so that it can be reused for the
+   /// next template. Pushed regions, buffered and kept Tokens are dropped,
+   /// but allocated capacity is kept. Syntax, tab width and limits are kept
+   /// as well.
+   ///
+   /// Each reset starts a new generation. pos_zero is not reset, it keeps
+   /// growing from where previous generation ended, thus Spans from previous
+   /// generations start before generation_pos_zero and span_slice refuses
+   /// them with RegionReleased. See DD-2026-10-16-09.
+   pub fn reset(&mut self) {
+      self.state = TokenizerState::ExpectInput;
+      self.index = 0;
+      self.pos_region = 0;
+      self.pos_line = 0;
+      self.pos_max = 0;
+      self.line = 0;
+      self.tokenbuf.clear();
+      self.region.clear();
+      self.region_open = None;
+      self.pos_max_open = 0;
+      self.cnt_openparen = 0;
+      self.cnt_closeparen = 0;
+      self.region_meta.clear();
+      self.state_snap.clear();
+      self.parse_error_prev = ParseError::None;
+      self.pos_zero_prev_instr = self.pos_zero;
+      self.state_prev_instr = TokenizerState::ExpectDefered;
+      self.pos_zero_prev_tag = self.pos_zero;
+      self.state_prev_interp = TokenizerState::ExpectDefered;
+      self.pos_zero_prev_interp = self.pos_zero;
+      self.state_prev_comment = TokenizerState::ExpectDefered;
+      self.pos_zero_prev_comment = self.pos_zero;
+      self.state_prev_verbatim = TokenizerState::ExpectDefered;
+      self.pos_zero_prev_verbatim = self.pos_zero;
+      self.tag_quote = 0;
+      self.tag_attr = TagAttr::Name;
+      self.raw_text = TextContext::Text;
+      self.state_prev_markup = TokenizerState::ExpectDefered;
+      self.pos_zero_prev_markup = self.pos_zero;
+      self.pos_utf8_invalid = usize::MAX;
+      self.utf8_error = None;
+      self.newline_warning = None;
+      self.cnt_error = 0;
+      self.cnt_warning = 0;
+      self.trim_right = None;
+      self.trim_instr = false;
+      self.lookahead.clear();
+      self.lookahead_pos = 0;
+      self.lookahead_base = 0;
+      self.checkpoint_live.clear();
+      self.generation += 1;
+      self.pos_zero_generation = self.pos_zero;
+   }
+
+   /// Returns the number of times Tokenizer was reset.
+   pub fn generation(&self) -> usize {
+      self.generation
+   }
+
+   /// Returns pos_zero at which current generation starts, see reset.
+   pub fn generation_pos_zero(&self) -> usize {
+      self.pos_zero_generation
+   }
+
    // Each time when fatal error is returned, it is necessary to set Tokenizer
    // state to Failed, but i already keep forgetting to do that too often, thus
    // create function to resolve that and forget this forever.
@@ -419,10 +932,9 @@ impl Tokenizer {
 
 
    // Function that allows us to push token into tokenbuf.
-   // It is allowed to use tokenbuf in all-in/all-out manner only.
    #[inline(always)]
    fn tokenbuf_push(&mut self, tok: Token) -> Result<(), Token> {
-      if let Err(token) = self.tokenbuf.append(tok) {
+      if let Err(token) = self.tokenbuf.push_back(tok) {
          return Err(self.fail_token(token));
       }
 
@@ -432,21 +944,15 @@ impl Tokenizer {
 
 
    // Function that takes one token out of tokenbuf.
-   // It is allowed to use tokenbuf in all-in/all-out manner only.
    #[inline(always)]
-   fn tokenbuf_consume(&mut self) -> Result<Option<Token>, Token> {
+   fn tokenbuf_consume(&mut self) -> Option<Token> {
       #[cfg(feature = "dbg_tokenbuf_verbose")] {
          println!("Tokenbuf: consume index: {}, pos_region: {}",
             self.index, self.pos_region
          );
       }
 
-      match self.tokenbuf.popleft() {
-         Ok(some_token) => Ok(some_token),
-         Err(token) => {
-            Err(self.fail_token(token))
-         }
-      }
+      self.tokenbuf.pop_front()
    }
 
 
@@ -455,11 +961,63 @@ impl Tokenizer {
    /// should be called each time @include, @require or similar directive is
    /// handled by outer code.
    ///
+   /// Source is expected to be UTF-8. UTF-8 BOM at source start is removed.
+   /// Source is tokenized as bytes even if it is not valid UTF-8, but
+   /// ParseError::InvalidUtf8 is returned right after Token that envelops the
+   /// first invalid byte.
+   ///
    /// This function does not return any meaningful successful result token, just
    /// None, but we define this signature for easier code reuse.
    pub fn src_push(&mut self, filename: Option<&str>, buf: Vec<u8>)
       -> Result<Option<Token>, Token>
    {
+      if let Err(token) = self.src_push_region(filename, Cow::Owned(buf)) {
+         return Err(token);
+      }
+
+      self.src_utf8_validate(self.index, true);
+
+      Ok(None)
+   }
+
+
+
+   /// Function that pushes borrowed template source into Tokenizers input
+   /// vector, the same way as src_push does, but source is not copied. This
+   /// is intended for templates embedded with include_bytes! or memory mapped
+   /// files. Source must outlive Tokenizer, span_slice returns slices of it.
+   ///
+   /// If source starts with UTF-8 BOM, it is skipped without copying as well.
+   pub fn src_push_borrowed(&mut self, filename: Option<&str>, buf: &'src [u8])
+      -> Result<Option<Token>, Token>
+   {
+      if let Err(token) = self.src_push_region(filename, Cow::Borrowed(buf)) {
+         return Err(token);
+      }
+
+      self.src_utf8_validate(self.index, true);
+
+      Ok(None)
+   }
+
+
+
+   // Push region, region meta and state snapshot, and switch Tokenizer to the
+   // start of pushed region. UTF-8 is validated by caller.
+   fn src_push_region(&mut self, filename: Option<&str>, buf: Cow<'src, [u8]>)
+      -> Result<Option<Token>, Token>
+   {
+      // Peeked Tokens are tokenized from current region already, pushed region
+      // would be tokenized after them, not where consumer is at.
+      if self.lookahead_pos < self.lookahead.len() {
+         return Err(Token::Error(ParseError::LookaheadError(Source {
+            pos_zero: self.pos_zero,
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 25,
+         })));
+      }
+
       let ss = &mut self.state_snap;
 
       // We do not want to panic if there is not enough memory.
@@ -523,6 +1081,12 @@ impl Tokenizer {
          line: self.line,
 
          filename: fname,
+         pos_utf8: 0,
+         pos_utf8_invalid: usize::MAX,
+         bom: false,
+         newline: NewlineStyle::Unknown,
+         pos_zero_end: usize::MAX,
+         released: false,
       });
 
       let r = &mut self.region;
@@ -549,6 +1113,7 @@ impl Tokenizer {
       self.pos_region = 0;
       self.pos_line = 0;
       self.line = 0;
+      self.pos_utf8_invalid = usize::MAX;
 
       // Change mode only if there was no input. Otherwise whoever appended
       // input is responsible to manage tokenizer state. This is by design so,
@@ -562,73 +1127,446 @@ impl Tokenizer {
 
 
 
+   /// Function that pushes template source into Tokenizers input vector the
+   /// same way as src_push does, but region is left open, thus more bytes can
+   /// be appended to it with src_append, i.e. when template is read from a
+   /// pipe or a large file in blocks. Only one region can be open at a time.
+   ///
+   /// While region is open, it is tokenized only till the last appended
+   /// newline, and Iterator returns None when it needs more input, instead of
+   /// treating the end of appended bytes as the end of region. Thus tokens
+   /// that straddle chunks, i.e. "@incl" + "ude(", are tokenized the same way
+   /// as if whole source was pushed at once. Iteration can be continued after
+   /// more bytes are appended. Once all bytes are appended, region must be
+   /// closed with src_close, so that the rest of it is tokenized.
+   pub fn src_push_open(&mut self, filename: Option<&str>, buf: Vec<u8>)
+      -> Result<Option<Token>, Token>
+   {
+      if self.region_open.is_some() {
+         return Err(Token::Error(ParseError::StreamError(Source {
+            pos_zero: self.pos_zero,
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 14,
+         })));
+      }
+
+      if let Err(token) = self.src_push_region(filename, Cow::Owned(buf)) {
+         return Err(token);
+      }
+
+      self.region_open = Some(self.index);
+      self.pos_max_open = 0;
+      self.src_utf8_validate(self.index, false);
+      self.src_max_open();
+
+      Ok(None)
+   }
+
+
+
+   /// Function that appends bytes to the region that was pushed with
+   /// src_push_open. Bytes are appended to the same region, thus Span::index
+   /// stays the same for the whole stream and span_slice works as usual.
+   ///
+   /// It is allowed to append bytes while Tokenizer is tokenizing a region
+   /// that was included from open region.
+   pub fn src_append(&mut self, buf: &[u8]) -> Result<Option<Token>, Token> {
+      let index = match self.region_open {
+         Some(index) => index,
+         None => {
+            return Err(Token::Error(ParseError::StreamError(Source {
+               pos_zero: self.pos_zero,
+               component: Component::Tokenizer,
+               line: line!(),
+               code: 15,
+            })));
+         }
+      };
+
+      // We do not want to panic if there is not enough memory. Open region is
+      // always owned, thus to_mut does not copy it.
+      let r = self.region[index].to_mut();
+      if let Err(..) = r.try_reserve(buf.len()) {
+         return Err(self.fail_token(
+            Token::Fatal(ParseError::NoMemory(Source {
+               pos_zero: self.pos_zero,
+               component: Component::Tokenizer,
+               line: line!(),
+               code: 0,
+            }))
+         ));
+      }
+
+      r.extend_from_slice(buf);
+      self.src_utf8_validate(index, false);
+      self.src_max_open();
+
+      Ok(None)
+   }
+
+
+
+   /// Function that closes region that was pushed with src_push_open. After
+   /// this Tokenizer treats the end of appended bytes as the end of region,
+   /// thus blocks that are not closed are reported as errors.
+   pub fn src_close(&mut self) -> Result<Option<Token>, Token> {
+      let index = match self.region_open {
+         Some(index) => index,
+         None => {
+            return Err(Token::Error(ParseError::StreamError(Source {
+               pos_zero: self.pos_zero,
+               component: Component::Tokenizer,
+               line: line!(),
+               code: 16,
+            })));
+         }
+      };
+
+      self.region_open = None;
+      self.src_utf8_validate(index, true);
+      self.pos_max_open = self.region[index].len();
+
+      if self.index == index {
+         self.pos_max = self.pos_max_open;
+      }
+
+      Ok(None)
+   }
+
+
+
+   /// Release bytes of regions, that are tokenized completely and whose Tokens
+   /// all end before pos_zero, i.e. included files, when consumer is done
+   /// with Tokens before given position. span_slice refuses Spans into
+   /// released regions with RegionReleased, region meta is kept. Regions of
+   /// Tokens, that are peeked or kept for rewind, are not released. Root
+   /// region is never released, Tokenizer returns to it till the end.
+   ///
+   /// Nothing is released unless this is called. Returns the number of
+   /// regions released by this call, see DD-2026-10-16-10.
+   pub fn region_release(&mut self, pos_zero: usize) -> usize {
+      // Kept Tokens can be returned again, thus their regions must stay.
+      // Without checkpoint only peeked Tokens are returned again.
+      let idx_kept = match self.checkpoint_live.is_empty() {
+         true => self.lookahead_pos,
+         false => 0,
+      };
+
+      let mut pos_zero_max = pos_zero;
+      for token in &self.lookahead[idx_kept..] {
+         if let Some(span) = token.span_clone() {
+            pos_zero_max = pos_zero_max.min(span.pos_zero);
+            break;
+         }
+      }
+
+      let mut cnt_released = 0;
+      for (index, meta) in self.region_meta.iter_mut().enumerate() {
+         if meta.released
+         || meta.pos_zero_end == usize::MAX
+         || meta.pos_zero_end > pos_zero_max
+         {
+            continue;
+         }
+
+         // Owned bytes are freed here, borrowed ones are not ours to free,
+         // but they are not reachable through span_slice any more.
+         self.region[index] = Cow::Borrowed(&[]);
+         meta.released = true;
+         cnt_released += 1;
+      }
+
+      cnt_released
+   }
+
+
+
+   // Move pos_max_open right after the last newline in open region. Lone "\r"
+   // is a newline as well, but "\r" at the end of region is held back, since
+   // it may be the first half of "\r\n", that is not appended yet.
    #[inline(always)]
-   fn defered_tokenize(&mut self) -> Option<Token> {
-      let src = &self.region[self.index];
-      let pos_start = self.pos_region;
-      let pos_max = src.len();
+   fn src_max_open(&mut self) {
+      let index = match self.region_open {
+         Some(index) => index,
+         None => return,
+      };
 
-      let pos_line_start = self.pos_region - self.pos_line;
-      let pos_token_start = self.pos_region;
+      let pos_from = self.pos_max_open;
+      let src = &self.region[index];
+      let mut pos_end = src.len();
+      if pos_end > pos_from && src[pos_end - 1] == 0x0D /* \r */ {
+         pos_end -= 1;
+      }
 
-      let line = self.line;
-      let mut pos = pos_start;
-      while pos < pos_max {
-         match src[pos] {
-            0x0A /* newline */ => {
-               // In a way we do not care if there is carriage return or not,
-               // since we just need to count lines. Well... if there are some
-               // problems iwth file and some lines are ended with "\r\n" some
-               // with "\n", we can not detect it. But should we?
-
-               let pos_in_line = pos_token_start - pos_line_start;
-               let len_defered = pos - pos_token_start;
-               let len_prev_token = pos_token_start - self.pos_region;
-
-               if let Err(token) = self.tokenbuf.append(Token::Real(
-                  TokenBody::Newline(Span {
-                     index: self.index,
-                     pos_region: pos,
-                     pos_zero: self.pos_zero + len_prev_token + len_defered,
-                     pos_line: pos_in_line + len_defered,
-                     line: line,
-                     length: 1,
-                  })
-               )){
-                  return Some(token);
-               };
+      if let Some(pos) = src[pos_from..pos_end].iter()
+         .rposition(|&b| b == 0x0A /* newline */ || b == 0x0D /* \r */)
+      {
+         self.pos_max_open = pos_from + pos + 1;
+      }
 
-               return self.return_tokenized(Token::Real(TokenBody::Defered(
-                  Span {
-                     index: self.index,
-                     pos_region: pos_token_start,
-                     pos_zero: self.pos_zero + len_prev_token,
-                     pos_line: pos_in_line,
-                     line: line,
-                     length: len_defered,
-                  }
-               )));
+      if self.index == index {
+         self.pos_max = self.pos_max_open;
+      }
+   }
+
+
+
+   // Validate region at given index as UTF-8, starting from position till
+   // which it was validated before. If region is not complete, incomplete
+   // character at the end is validated once more bytes are appended. Only the
+   // first invalid byte in region is reported, see pos_utf8_invalid.
+   //
+   // UTF-8 BOM at region start is removed, thus positions and columns in the
+   // first line are the same as seen in editor. This is done before anything
+   // in region is tokenized, since BOM does not contain newline.
+   fn src_utf8_validate(&mut self, index: usize, complete: bool) {
+      let meta = &mut self.region_meta[index];
+      let src = &mut self.region[index];
+
+      if meta.pos_utf8 == usize::MAX {
+         return;
+      }
+
+      if meta.pos_utf8 == 0 && !meta.bom {
+         if src.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            // Borrowed region is not copied, BOM is skipped by slicing it.
+            match src {
+               Cow::Borrowed(buf) => {
+                  *src = Cow::Borrowed(&buf[3..]);
+               }
+               Cow::Owned(buf) => {
+                  buf.drain(..3);
+               }
             }
+            meta.bom = true;
 
-            0x40 /* @ */ => {
-               return self.instruction_tokenize(pos, pos_start, pos_max, line, line);
+            if self.index == index && self.region_open != Some(index) {
+               self.pos_max = src.len();
+            }
+         }
+         else if !complete && [0xEF, 0xBB, 0xBF].starts_with(&src[..]) {
+            // It is not known yet if this is BOM.
+            return;
+         }
+      }
+
+      match std::str::from_utf8(&src[meta.pos_utf8..]) {
+         Ok(..) => {
+            meta.pos_utf8 = src.len();
+         }
+         Err(e) => {
+            let pos = meta.pos_utf8 + e.valid_up_to();
+
+            if e.error_len().is_none() && !complete {
+               meta.pos_utf8 = pos;
             }
+            else {
+               meta.pos_utf8 = usize::MAX;
+               meta.pos_utf8_invalid = pos;
 
-            _ch => {
-               #[cfg(feature = "dbg_tokenizer_verbose")]{
-                  println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
+               if self.index == index {
+                  self.pos_utf8_invalid = pos;
                }
             }
          }
-
-         pos += 1;
       }
+   }
 
-      //
-      // Code below deals with correct leftover handling. It either returns
-      // last available bytes as Defered or consumes current region and does
-      // state_snap.pop().
-      //
+
+
+   // Tokenizer has returned Token, that starts at given position and
+   // envelops the first invalid UTF-8 byte in current region. Store error for
+   // Iterator to return it right after Token.
+   fn src_utf8_report(&mut self, pos_region: usize, pos_zero: usize) {
+      let pos_invalid = self.pos_utf8_invalid;
+
+      self.utf8_error = Some(ParseError::InvalidUtf8(Source {
+         pos_zero: pos_zero + (pos_invalid - pos_region),
+         component: Component::Tokenizer,
+         line: line!(),
+         code: 17,
+      }));
+
+      self.pos_utf8_invalid = usize::MAX;
+      self.region_meta[self.index].pos_utf8_invalid = usize::MAX;
+   }
+
+
+
+   // Compare line ending of returned Newline with the first Newline in current
+   // region. If it differs, store MixedNewline warning for Iterator to return
+   // it right after Newline. Warning is given once per region.
+   #[inline(always)]
+   fn newline_style_check(&mut self, span: Span) {
+      let style = if span.length == 2 {
+         NewlineStyle::CrLf
+      }
+      else if self.region[self.index][span.pos_region] == 0x0D /* \r */ {
+         NewlineStyle::Cr
+      }
+      else {
+         NewlineStyle::Lf
+      };
+
+      let meta = &mut self.region_meta[self.index];
+      match meta.newline {
+         NewlineStyle::Unknown => {
+            meta.newline = style;
+         }
+         NewlineStyle::Mixed => { }
+         first if first != style => {
+            meta.newline = NewlineStyle::Mixed;
+            self.newline_warning = Some(ParseError::MixedNewline(Source {
+               pos_zero: span.pos_zero,
+               component: Component::Tokenizer,
+               line: line!(),
+               code: 18,
+            }));
+         }
+         _ => { }
+      }
+   }
+
+
+
+   // Returns true if current region is open for src_append. In such a case the
+   // end of region is not known yet, thus tokenization functions that reach
+   // it must return None without changing Tokenizer's state, instead of
+   // returning what's left or errors for blocks that are not closed.
+   #[inline(always)]
+   fn src_wait(&self) -> bool {
+      self.region_open == Some(self.index)
+   }
+
+
+
+   #[inline(always)]
+   fn defered_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+
+      let sigil = self.syntax.sigil;
+      let open_first = self.syntax.open_first;
+      let raw_open_first = self.syntax.raw_open_first;
+      let special = &self.syntax.text_special;
+
+      let line = self.line;
+      let mut pos = pos_start;
+      while pos < pos_max {
+         // Most of the bytes in text are not special, Tokenizer jumps right
+         // to the next special byte, see ByteSet.find.
+         let pos_special = special.find(src, pos);
+
+         #[cfg(feature = "dbg_tokenizer_verbose")]{
+            if pos_special > pos {
+               println!("non-special chars pos: {}..{}, do nothing", pos, pos_special);
+            }
+         }
+
+         pos = pos_special;
+         if pos >= pos_max {
+            break;
+         }
+
+         match src[pos] {
+            0x0A /* newline */ | 0x0D /* \r */ => {
+               let span = self.span_at(pos, newline_len(src, pos), line);
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(TokenBody::Newline(span))
+               );
+            }
+
+            0x3C /* < */ => {
+               let body = match tag_match(src, pos) {
+                  Tag::OpenStart(start, end) => {
+                     TokenBody::TagOpenStart(self.span_at(start, end - start + 1, line))
+                  }
+                  Tag::CloseStart(start, end) => {
+                     TokenBody::TagCloseStart(self.span_at(start, end - start + 1, line))
+                  }
+                  Tag::Close(start, end) => {
+                     TokenBody::TagClose(self.span_at(start, end - start + 1, line))
+                  }
+                  Tag::HtmlCommentOpen(start, end) => {
+                     TokenBody::HtmlCommentOpen(self.span_at(start, end - start + 1, line))
+                  }
+                  Tag::Doctype(start, end) => {
+                     TokenBody::Doctype(self.span_at(start, end - start + 1, line))
+                  }
+                  Tag::CdataOpen(start, end) => {
+                     TokenBody::CdataOpen(self.span_at(start, end - start + 1, line))
+                  }
+                  Tag::None => {
+                     // This is not a tag, thus "<" has no known context. It
+                     // could be broken tag or unescaped "&lt;".
+                     let span = self.span_at(pos, 1, line);
+                     return self.return_tokenized_warn_after(pos_start, pos,
+                        line, Token::Real(TokenBody::Lt(span)),
+                        Token::Warning(ParseError::UnescapedLt(Source {
+                           pos_zero: span.pos_zero,
+                           component: Component::Tokenizer,
+                           line: line!(),
+                           code: 5,
+                        }))
+                     );
+                  }
+               };
+
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(body)
+               );
+            }
+
+            // Tags are closed in tag_tokenize, thus ">" in text does not
+            // close anything.
+            0x3E /* > */ => {
+               let span = self.span_at(pos, 1, line);
+               return self.return_tokenized_warn_after(pos_start, pos,
+                  line, Token::Real(TokenBody::Gt(span)),
+                  Token::Warning(ParseError::UnescapedGt(Source {
+                     pos_zero: span.pos_zero,
+                     component: Component::Tokenizer,
+                     line: line!(),
+                     code: 6,
+                  }))
+               );
+            }
+
+            // Sigil and delimiters are configurable, see Syntax. They can
+            // not be newline, "<" or ">", thus they are matched last.
+            byte if byte == sigil => {
+               return self.instruction_tokenize(pos, pos_start, pos_max, line, line);
+            }
+
+            byte if byte == open_first || byte == raw_open_first => {
+               if self.comment_match(src, pos) {
+                  return self.comment_open(pos_start, pos, line);
+               }
+
+               if let Some(body) = self.interpolation_match(src, pos, line, TextContext::Text) {
+                  return self.return_interpolation_after(pos_start, pos, line,
+                     body
+                  );
+               }
+            }
+
+            _ch => {
+               #[cfg(feature = "dbg_tokenizer_verbose")]{
+                  println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
+               }
+            }
+         }
+
+         pos += 1;
+      }
+
+      //
+      // Code below deals with correct leftover handling. It either returns
+      // last available bytes as Defered or consumes current region and does
+      // state_snap.pop().
+      //
 
       if pos_start < pos_max {
          let len_defered = pos_max - pos_start;
@@ -642,10 +1580,1144 @@ impl Tokenizer {
             length: len_defered
          }));
 
-         return self.return_tokenized(token);
+         return self.return_tokenized(token);
+      }
+
+      None
+   }
+
+
+
+   // Build Span for region slice that starts at pos_region and is in the same
+   // line as Tokenizer's current position. This is a helper for tokenization
+   // functions, which return at first newline, thus all bytes between
+   // self.pos_region and pos_region are known to be in current line.
+   #[inline(always)]
+   fn span_at(&self, pos_region: usize, length: usize, line: usize) -> Span {
+      let len_prev = pos_region - self.pos_region;
+
+      Span {
+         index: self.index,
+         pos_region: pos_region,
+         pos_zero: self.pos_zero + len_prev,
+         pos_line: self.pos_line + len_prev,
+         line: line,
+         length: length,
+      }
+   }
+
+
+
+   // Return given Token, but if there are bytes between pos_start and pos, they
+   // must be returned as a Token built with body_before (usually Defered)
+   // before it. In such a case given Token is buffered.
+   //
+   // This function expects that pos_start is at Tokenizer's current position
+   // and that given Token starts at pos.
+   #[inline(always)]
+   fn return_tokenized_after(&mut self, body_before: fn(Span) -> TokenBody,
+      pos_start: usize, pos: usize, line: usize, tok: Token
+   )
+      -> Option<Token>
+   {
+      if pos <= pos_start {
+         return self.return_tokenized(tok);
+      }
+
+      if let Err(token) = self.tokenbuf_push(tok) {
+         return Some(token);
+      }
+
+      let span = self.span_at(pos_start, pos - pos_start, line);
+      self.return_tokenized(Token::Real(body_before(span)))
+   }
+
+
+
+   // The same as return_tokenized_after, but given warning Token is returned
+   // right after given Token.
+   #[inline(always)]
+   fn return_tokenized_warn_after(&mut self, pos_start: usize, pos: usize,
+      line: usize, tok: Token, warning: Token
+   )
+      -> Option<Token>
+   {
+      if pos <= pos_start {
+         if let Err(token) = self.tokenbuf_push(warning) {
+            return Some(token);
+         }
+
+         return self.return_tokenized(tok);
+      }
+
+      if let Err(token) = self.tokenbuf_push(tok) {
+         return Some(token);
+      }
+
+      if let Err(token) = self.tokenbuf_push(warning) {
+         return Some(token);
+      }
+
+      let span = self.span_at(pos_start, pos - pos_start, line);
+      self.return_tokenized(Token::Real(TokenBody::Defered(span)))
+   }
+
+
+
+   // The same as return_tokenized_after with Defered, but given Token has
+   // trim marker, i.e. "{{-" or "@-endif", thus whitespace right before it is
+   // returned as Phantom. Whitespace is trimmed back to pos_start, which is
+   // at most at the line start. See DD-2026-10-16-07.
+   #[inline(always)]
+   fn return_tokenized_trim_after(&mut self, pos_start: usize, pos: usize,
+      line: usize, tok: Token
+   )
+      -> Option<Token>
+   {
+      let pos_trim = self.trim_left_start(pos_start, pos);
+      if pos_trim >= pos {
+         return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+            line, tok
+         );
+      }
+
+      let trim = self.trim_left_token(pos_trim, pos, line);
+      if pos_trim <= pos_start {
+         if let Err(token) = self.tokenbuf_push(tok) {
+            return Some(token);
+         }
+
+         return self.return_tokenized(trim);
+      }
+
+      if let Err(token) = self.tokenbuf_push(trim) {
+         return Some(token);
+      }
+
+      if let Err(token) = self.tokenbuf_push(tok) {
+         return Some(token);
+      }
+
+      let span = self.span_at(pos_start, pos_trim - pos_start, line);
+      self.return_tokenized(Token::Real(TokenBody::Defered(span)))
+   }
+
+
+
+   // Return interpolation open Token, that starts at pos, after Defered before
+   // it. Delimiters can not contain "-", thus open delimiter ends with it only
+   // if it has trim marker, i.e. "{{-", then whitespace before it is trimmed.
+   #[inline(always)]
+   fn return_interpolation_after(&mut self, pos_start: usize, pos: usize,
+      line: usize, body: TokenBody
+   )
+      -> Option<Token>
+   {
+      let span = body.span_clone();
+      if self.region[self.index][span.pos_region + span.length - 1] == 0x2D /* - */ {
+         return self.return_tokenized_trim_after(pos_start, pos, line,
+            Token::Real(body)
+         );
+      }
+
+      self.return_tokenized_after(TokenBody::Defered, pos_start, pos, line,
+         Token::Real(body)
+      )
+   }
+
+
+
+   // Returns true if Tokenizer is in state, where text is tokenized like in
+   // text context, thus trim markers trim whitespace: text, raw-text element
+   // content, HTML comment or CDATA section. Within tags whitespace is a part
+   // of tag syntax.
+   #[inline(always)]
+   fn trim_state(&self) -> bool {
+      matches!(self.state,
+         TokenizerState::ExpectDefered
+         | TokenizerState::ExpectRawTextEnd
+         | TokenizerState::ExpectHtmlCommentClose
+         | TokenizerState::ExpectCdataClose
+      )
+   }
+
+
+
+   // Returns position where whitespace right before pos starts, but not
+   // before pos_start. If there is no whitespace, pos is returned.
+   #[inline(always)]
+   fn trim_left_start(&self, pos_start: usize, pos: usize) -> usize {
+      let src = &self.region[self.index];
+
+      let mut pos_trim = pos;
+      while pos_trim > pos_start
+      && matches!(src[pos_trim - 1], 0x20 /* space */ | 0x09 /* tab */)
+      {
+         pos_trim -= 1;
+      }
+
+      pos_trim
+   }
+
+
+
+   // Build Phantom Token for whitespace from pos_trim till pos, that is
+   // trimmed before Token with trim marker. Whitespace at the line start is
+   // leading whitespace, see DD-2023-07-15-01.
+   #[inline(always)]
+   fn trim_left_token(&self, pos_trim: usize, pos: usize, line: usize) -> Token {
+      let span = self.span_at(pos_trim, pos - pos_trim, line);
+
+      match span.pos_line {
+         0 => Token::Phantom(TokenBody::WhiteSpaceLd(span)),
+         _ => Token::Phantom(TokenBody::WhiteSpace(span)),
+      }
+   }
+
+
+
+   // Tokenize whitespace after Token with trim marker, i.e. "-}}". Whitespace
+   // till the end of line is returned as Phantom, and so is the newline, so
+   // that line with only a directive does not leave blank line in output.
+   // Whitespace before text in the same line is trimmed as well. See
+   // DD-2026-10-16-07.
+   //
+   // Returns None and clears trim_right if there is no whitespace, then
+   // caller tokenizes text as usual. If trim_right is still set, None means
+   // that open region needs more input.
+   #[inline(never)]
+   fn trim_right_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+
+      let mut pos = pos_start;
+      while pos < pos_max && matches!(src[pos], 0x20 /* space */ | 0x09 /* tab */) {
+         pos += 1;
+      }
+
+      // Newline can follow in bytes, that are not appended yet.
+      if pos >= pos_max && self.src_wait() {
+         return None;
+      }
+
+      self.trim_right = None;
+
+      let line = self.line;
+      if pos < pos_max && matches!(src[pos], 0x0A /* newline */ | 0x0D /* \r */) {
+         let span = self.span_at(pos, newline_len(src, pos), line);
+         let newline = Token::Phantom(TokenBody::Newline(span));
+         if pos == pos_start {
+            return self.return_tokenized(newline);
+         }
+
+         if let Err(token) = self.tokenbuf_push(newline) {
+            return Some(token);
+         }
+
+         let span = self.span_at(pos_start, pos - pos_start, line);
+         return self.return_tokenized(Token::Phantom(TokenBody::WhiteSpaceTr(span)));
+      }
+
+      if pos > pos_start {
+         let span = self.span_at(pos_start, pos - pos_start, line);
+         return self.return_tokenized(Token::Phantom(TokenBody::WhiteSpace(span)));
+      }
+
+      None
+   }
+
+
+
+   // Try to match interpolation start at given position, i.e. "{{" or "{!!"
+   // for default Syntax. Returns None if bytes at given position do not start
+   // interpolation. Matched token is tagged with given context. Trim marker
+   // is a part of matched token, i.e. "{{-".
+   #[inline(always)]
+   fn interpolation_match(&self, src: &[u8], pos: usize, line: usize,
+      context: TextContext
+   )
+      -> Option<TokenBody>
+   {
+      let open = &self.syntax.open;
+      if delimiter_match(src, pos, open) {
+         let length = open.len() + trim_marker_len(src, pos + open.len());
+         let span = self.span_at(pos, length, line);
+         return Some(TokenBody::InterpolationOpen(span, context));
+      }
+
+      let raw_open = &self.syntax.raw_open;
+      if delimiter_match(src, pos, raw_open) {
+         let length = raw_open.len() + trim_marker_len(src, pos + raw_open.len());
+         let span = self.span_at(pos, length, line);
+         return Some(TokenBody::RawInterpolationOpen(span, context));
+      }
+
+      None
+   }
+
+
+
+   // Tokenize what's inside interpolation after "{{" or "{!!".
+   //
+   // Everything is returned as Expression untill matching close delimiter is
+   // found, i.e. "}}" for "{{" and "!!}" for "{!!". Tokenizer does not try to
+   // understand expression, it is left for later stages.
+   #[inline(always)]
+   fn interpolation_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+      let raw = self.state == TokenizerState::ExpectRawInterpolationClose;
+      let close: &[u8] = match raw {
+         true => &self.syntax.raw_close,
+         false => &self.syntax.close,
+      };
+      let close_first = close[0];
+
+      let line = self.line;
+      let mut pos = pos_start;
+      while pos < pos_max {
+         match src[pos] {
+            0x0A /* newline */ | 0x0D /* \r */ => {
+               let span = self.span_at(pos, newline_len(src, pos), line);
+               return self.return_tokenized_after(TokenBody::Expression,
+                  pos_start, pos, line, Token::Real(TokenBody::Newline(span))
+               );
+            }
+
+            byte if byte == close_first && delimiter_match(src, pos, close) => {
+               // Trim marker is a part of close token, i.e. "-}}", but not
+               // if it belongs to open token, i.e. "{{-}}".
+               let pos_close = match pos > pos_start && src[pos - 1] == 0x2D /* - */ {
+                  true => pos - 1,
+                  false => pos,
+               };
+
+               let pos_end = pos + close.len();
+               if pos_close < pos {
+                  self.trim_right = Some((self.index, pos_end));
+               }
+
+               let span = self.span_at(pos_close, pos_end - pos_close, line);
+               let body = match raw {
+                  true => TokenBody::RawInterpolationClose(span),
+                  false => TokenBody::InterpolationClose(span),
+               };
+               return self.return_tokenized_after(TokenBody::Expression,
+                  pos_start, pos_close, line, Token::Real(body)
+               );
+            }
+
+            _ch => {
+               #[cfg(feature = "dbg_tokenizer_verbose")]{
+                  println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
+               }
+            }
+         }
+
+         pos += 1;
+      }
+
+      if self.src_wait() {
+         return None;
+      }
+
+      // Being here means that interpolation was not closed within current
+      // region. Return what's left as Expression and inform user about it the
+      // same way as it is done for instructions.
+
+      if pos_start < pos_max {
+         let span = self.span_at(pos_start, pos_max - pos_start, line);
+
+         if let Err(token) = self.tokenbuf_push(Token::Real(TokenBody::Expression(span))) {
+            return Some(token);
+         }
+      }
+
+      if let Err(token) = self.tokenbuf_push(Token::Error(
+         ParseError::OpenInterpolation(Source {
+            pos_zero: self.pos_zero_prev_interp,
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 4,
+         })
+      )) {
+         return Some(token);
+      }
+
+      self.state = self.state_prev_interp;
+      Some(Token::StateChange)
+   }
+
+
+
+   // Tokenize content of raw-text element, i.e. what's between "<script>" and
+   // "</script>".
+   //
+   // Everything is returned as Defered untill closing tag for the same
+   // element is found, i.e. "</script>" or "</SCRIPT  >". Other tags, "<" and
+   // ">" are not special here. Instructions, comments and interpolations are
+   // tokenized as in text, but interpolations get element's TextContext, so
+   // that escaping can differ later.
+   #[inline(always)]
+   fn raw_text_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+      let context = self.raw_text;
+      let sigil = self.syntax.sigil;
+      let open_first = self.syntax.open_first;
+      let raw_open_first = self.syntax.raw_open_first;
+
+      let line = self.line;
+      let mut pos = pos_start;
+      while pos < pos_max {
+         match src[pos] {
+            0x0A /* newline */ | 0x0D /* \r */ => {
+               let span = self.span_at(pos, newline_len(src, pos), line);
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(TokenBody::Newline(span))
+               );
+            }
+
+            byte if byte == sigil => {
+               return self.instruction_tokenize(pos, pos_start, pos_max, line, line);
+            }
+
+            byte if byte == open_first || byte == raw_open_first => {
+               if self.comment_match(src, pos) {
+                  return self.comment_open(pos_start, pos, line);
+               }
+
+               if let Some(body) = self.interpolation_match(src, pos, line, context) {
+                  return self.return_interpolation_after(pos_start, pos, line,
+                     body
+                  );
+               }
+            }
+
+            // Only closing tag for the same element ends raw text. "</>" does
+            // not, since it has no name.
+            0x3C /* < */ => {
+               let body = match tag_match(src, pos) {
+                  Tag::CloseStart(start, end)
+                  if tag_raw_text(src, start + 2, end) == context => {
+                     TokenBody::TagCloseStart(self.span_at(start, end - start + 1, line))
+                  }
+                  Tag::Close(start, end)
+                  if tag_raw_text(src, start + 2, end - 1) == context => {
+                     TokenBody::TagClose(self.span_at(start, end - start + 1, line))
+                  }
+                  _ => {
+                     pos += 1;
+                     continue;
+                  }
+               };
+
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(body)
+               );
+            }
+
+            _ch => {
+               #[cfg(feature = "dbg_tokenizer_verbose")]{
+                  println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
+               }
+            }
+         }
+
+         pos += 1;
+      }
+
+      // Raw-text element is not closed within current region. This is not an
+      // error on Tokenizer level, the same way as it is not for other
+      // elements.
+      if pos_start < pos_max {
+         let span = self.span_at(pos_start, pos_max - pos_start, line);
+         return self.return_tokenized(Token::Real(TokenBody::Defered(span)));
+      }
+
+      None
+   }
+
+
+
+   // Tokenize what's inside HTML comment "<!-- -->" or CDATA section
+   // "<![CDATA[ ]]>".
+   //
+   // HTML comment is part of output, thus instructions, template comments and
+   // interpolations are tokenized, see DD-2026-10-16-01. Everything else is
+   // returned as Defered, tags are not tokenized.
+   #[inline(always)]
+   fn markup_section_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+      let cdata = self.state == TokenizerState::ExpectCdataClose;
+      let sigil = self.syntax.sigil;
+      let open_first = self.syntax.open_first;
+      let raw_open_first = self.syntax.raw_open_first;
+
+      let line = self.line;
+      let mut pos = pos_start;
+      while pos < pos_max {
+         match src[pos] {
+            // Newline at the end of region can not be followed by close
+            // delimiter, thus it is handled as unclosed section below.
+            0x0A /* newline */ | 0x0D /* \r */
+               if pos + newline_len(src, pos) < pos_max
+            => {
+               let span = self.span_at(pos, newline_len(src, pos), line);
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(TokenBody::Newline(span))
+               );
+            }
+
+            byte if byte == sigil => {
+               return self.instruction_tokenize(pos, pos_start, pos_max, line, line);
+            }
+
+            byte if byte == open_first || byte == raw_open_first => {
+               if self.comment_match(src, pos) {
+                  return self.comment_open(pos_start, pos, line);
+               }
+
+               if let Some(body) = self.interpolation_match(src, pos, line, TextContext::Text) {
+                  return self.return_interpolation_after(pos_start, pos, line,
+                     body
+                  );
+               }
+            }
+
+            0x2D /* - */ if !cdata
+               && pos + 2 < pos_max
+               && src[pos + 1] == 0x2D /* - */
+               && src[pos + 2] == 0x3E /* > */
+            => {
+               let span = self.span_at(pos, 3, line);
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(TokenBody::HtmlCommentClose(span))
+               );
+            }
+
+            0x5D /* ] */ if cdata
+               && pos + 2 < pos_max
+               && src[pos + 1] == 0x5D /* ] */
+               && src[pos + 2] == 0x3E /* > */
+            => {
+               let span = self.span_at(pos, 3, line);
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(TokenBody::CdataClose(span))
+               );
+            }
+
+            _ch => {
+               #[cfg(feature = "dbg_tokenizer_verbose")]{
+                  println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
+               }
+            }
+         }
+
+         pos += 1;
+      }
+
+      // Being here means that section was not closed within current region.
+      let source = Source {
+         pos_zero: self.pos_zero_prev_markup,
+         component: Component::Tokenizer,
+         line: line!(),
+         code: if cdata { 13 } else { 12 },
+      };
+
+      let error = if cdata {
+         ParseError::OpenCdata(source)
+      }
+      else {
+         ParseError::OpenHtmlComment(source)
+      };
+
+      self.tokenize_unclosed(TokenBody::Defered, pos_start, line, error,
+         self.state_prev_markup
+      )
+   }
+
+
+
+   // Returns true if bytes at given position start template comment, i.e.
+   // "{{--" for default Syntax.
+   #[inline(always)]
+   fn comment_match(&self, src: &[u8], pos: usize) -> bool {
+      delimiter_match(src, pos, &self.syntax.comment_open)
+   }
+
+
+
+   // Switch Tokenizer into ExpectCommentClose state for comment that starts at
+   // pos. If there are bytes between pos_start and pos, they are returned as
+   // Defered and comment is tokenized on next call, otherwise comment is
+   // tokenized right away.
+   #[inline(always)]
+   fn comment_open(&mut self, pos_start: usize, pos: usize, line: usize)
+      -> Option<Token>
+   {
+      self.state_prev_comment = self.state;
+      self.state = TokenizerState::ExpectCommentClose;
+      self.pos_zero_prev_comment = self.pos_zero + (pos - self.pos_region);
+
+      if pos > pos_start {
+         let span = self.span_at(pos_start, pos - pos_start, line);
+         return self.return_tokenized(Token::Real(TokenBody::Defered(span)));
+      }
+
+      self.comment_tokenize()
+   }
+
+
+
+   // Tokenize template comment, that starts with "{{--" and ends with "--}}".
+   //
+   // Everything is returned as Comment untill "--}}" is found, nothing within
+   // comment is special, except newlines. If comment is not closed within
+   // current region, OpenComment error points to comment start, not to the end
+   // of region.
+   #[inline(always)]
+   fn comment_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+      let close: &[u8] = &self.syntax.comment_close;
+
+      let line = self.line;
+      let mut pos = pos_start;
+      while pos < pos_max {
+         match src[pos] {
+            // Newline at the end of region can not be followed by "--}}",
+            // thus it is handled as unclosed comment below.
+            0x0A /* newline */ | 0x0D /* \r */
+               if pos + newline_len(src, pos) < pos_max
+            => {
+               let span = self.span_at(pos, newline_len(src, pos), line);
+               return self.return_tokenized_after(TokenBody::Comment,
+                  pos_start, pos, line, Token::Real(TokenBody::Newline(span))
+               );
+            }
+
+            // Comment close delimiter always starts with "--".
+            0x2D /* - */ if delimiter_match(src, pos, close) => {
+               self.state = self.state_prev_comment;
+
+               let span = self.span_at(pos_start, pos + close.len() - pos_start, line);
+               return self.return_tokenized(Token::Real(TokenBody::Comment(span)));
+            }
+
+            _ch => {
+               #[cfg(feature = "dbg_tokenizer_verbose")]{
+                  println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
+               }
+            }
+         }
+
+         pos += 1;
+      }
+
+      // Being here means that comment was not closed within current region.
+      // Inform user about it the same way as it is done for interpolations.
+      let error = ParseError::OpenComment(Source {
+         pos_zero: self.pos_zero_prev_comment,
+         component: Component::Tokenizer,
+         line: line!(),
+         code: 10,
+      });
+
+      self.tokenize_unclosed(TokenBody::Comment, pos_start, line, error,
+         self.state_prev_comment
+      )
+   }
+
+
+
+   // Tokenize what's inside "@verbatim ... @endverbatim" block.
+   //
+   // Everything is returned as Defered untill "@endverbatim" is found, only
+   // newlines are returned as separate tokens, thus instructions, tags and
+   // interpolations reach output unchanged.
+   #[inline(always)]
+   fn verbatim_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+      let sigil = self.syntax.sigil;
+
+      let line = self.line;
+      let mut pos = pos_start;
+      while pos < pos_max {
+         match src[pos] {
+            // Newline at the end of region can not be followed by
+            // "@endverbatim", thus it is handled as unclosed block below.
+            0x0A /* newline */ | 0x0D /* \r */
+               if pos + newline_len(src, pos) < pos_max
+            => {
+               let span = self.span_at(pos, newline_len(src, pos), line);
+               return self.return_tokenized_after(TokenBody::Defered,
+                  pos_start, pos, line, Token::Real(TokenBody::Newline(span))
+               );
+            }
+
+            byte if byte == sigil => {
+               let pos_ident = ident_start(src, pos);
+               let pos_ident_end = ident_scan(src, pos_ident) - 1;
+               if let Ident::EndVerbatim(..) = ident_match(src, pos_ident, pos_ident_end) {
+                  if pos_ident > pos + 1 {
+                     self.trim_right = Some((self.index, pos_ident_end + 1));
+                  }
+
+                  let span = self.span_at(pos, pos_ident_end - pos + 1, line);
+                  return self.return_tokenized_after(TokenBody::Defered,
+                     pos_start, pos, line, Token::Real(TokenBody::EndVerbatim(span))
+                  );
+               }
+            }
+
+            _ch => {
+               #[cfg(feature = "dbg_tokenizer_verbose")]{
+                  println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
+               }
+            }
+         }
+
+         pos += 1;
+      }
+
+      // Being here means that "@endverbatim" was not found within current
+      // region.
+      let error = ParseError::OpenVerbatim(Source {
+         pos_zero: self.pos_zero_prev_verbatim,
+         component: Component::Tokenizer,
+         line: line!(),
+         code: 11,
+      });
+
+      self.tokenize_unclosed(TokenBody::Defered, pos_start, line, error,
+         self.state_prev_verbatim
+      )
+   }
+
+
+
+   // Return what's left in current region as a Token built with given body
+   // (and Newline, if region ends with it), followed by given error, and
+   // switch Tokenizer to given state. This is used for blocks that were not
+   // closed within current region, i.e. comments and verbatim blocks.
+   //
+   // Tokens are buffered, StateChange is returned.
+   #[inline(always)]
+   fn tokenize_unclosed(&mut self, body: fn(Span) -> TokenBody,
+      pos_start: usize, line: usize, error: ParseError, state: TokenizerState
+   )
+      -> Option<Token>
+   {
+      if self.src_wait() {
+         return None;
+      }
+
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_max = src.len();
+
+      let mut pos_end = pos_max;
+      if pos_start < pos_max && newline_last(src, pos_max - 1) {
+         pos_end -= 1;
+
+         if src[pos_end] == 0x0A && pos_start < pos_end && src[pos_end - 1] == 0x0D {
+            pos_end -= 1;
+         }
+      }
+
+      if pos_start < pos_end {
+         let span = self.span_at(pos_start, pos_end - pos_start, line);
+
+         if let Err(token) = self.tokenbuf_push(Token::Real(body(span))) {
+            return Some(token);
+         }
+      }
+
+      if pos_end < pos_max {
+         let span = self.span_at(pos_end, pos_max - pos_end, line);
+
+         if let Err(token) = self.tokenbuf_push(Token::Real(TokenBody::Newline(span))) {
+            return Some(token);
+         }
+      }
+
+      if let Err(token) = self.tokenbuf_push(Token::Error(error)) {
+         return Some(token);
+      }
+
+      self.state = state;
+      Some(Token::StateChange)
+   }
+
+
+
+   // Tokenize what's inside closing tag after TagCloseStart, i.e. "</div  >".
+   //
+   // Everything is returned as Defered untill ">" is matched. Quotes are
+   // skipped, so that broken closing tag "</a title='x > y'>" ends only at the
+   // last ">". Instructions are allowed within tags, thus "@" is handled the
+   // same way as in Defered text.
+   #[inline(always)]
+   fn tag_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+      let sigil = self.syntax.sigil;
+
+      let line = self.line;
+      let mut quote = self.tag_quote;
+      let mut pos = pos_start;
+      while pos < pos_max {
+         let byte = src[pos];
+
+         match byte {
+            0x0A /* newline */ | 0x0D /* \r */ => {
+               self.tag_quote = quote;
+
+               let span = self.span_at(pos, newline_len(src, pos), line);
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(TokenBody::Newline(span))
+               );
+            }
+
+            _ if byte == sigil => {
+               // Instruction can reach the end of open region, then nothing is
+               // tokenized and quote state must stay as it was, since the same
+               // bytes are scanned again once more input is appended.
+               let quote_prev = self.tag_quote;
+               self.tag_quote = quote;
+
+               let token = self.instruction_tokenize(pos, pos_start, pos_max, line, line);
+               if token.is_none() {
+                  self.tag_quote = quote_prev;
+               }
+
+               return token;
+            }
+
+            _ if quote != 0 => {
+               if byte == quote {
+                  quote = 0;
+               }
+            }
+
+            0x22 /* " */ | 0x27 /* ' */ => {
+               quote = byte;
+            }
+
+            0x3E /* > */ => {
+               self.tag_quote = 0;
+
+               let span = self.span_at(pos, 1, line);
+               return self.return_tokenized_after(TokenBody::Defered, pos_start, pos,
+                  line, Token::Real(TokenBody::TagClose(span))
+               );
+            }
+
+            _ch => {
+               #[cfg(feature = "dbg_tokenizer_verbose")]{
+                  println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
+               }
+            }
+         }
+
+         pos += 1;
+      }
+
+      self.tag_unclosed(TokenBody::Defered, pos_start, line)
+   }
+
+
+
+   // Tokenize what's inside open tag after TagOpenStart, i.e. attributes.
+   //
+   // Attributes are returned as AttrName, AttrEq and AttrValue tokens,
+   // whitespace between them as WhiteSpace. Quoted value is returned as
+   // AttrQuote, AttrValue, AttrQuote, thus AttrValue never envelops quotes.
+   // Quoted value can contain ">" and "/>", they do not end the tag.
+   // Instructions, template comments and interpolations are allowed in place
+   // of attribute name or value and within quoted value. Tag ends with ">",
+   // TagOpenEnd, or "/>", TagSelfClose.
+   #[inline(always)]
+   fn tag_attr_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+      let line = self.line;
+
+      if pos_start >= pos_max {
+         return self.tag_unclosed(TokenBody::Defered, pos_start, line);
+      }
+
+      if self.tag_quote != 0 {
+         return self.tag_attr_quoted_tokenize();
+      }
+
+      let byte = src[pos_start];
+      match byte {
+         0x0A /* newline */ | 0x0D /* \r */ => {
+            if self.tag_attr == TagAttr::Unquoted {
+               self.tag_attr = TagAttr::Name;
+            }
+
+            let span = self.span_at(pos_start, newline_len(src, pos_start), line);
+            return self.return_tokenized(Token::Real(TokenBody::Newline(span)));
+         }
+
+         0x20 /* space */ | 0x09 /* tab */ => {
+            if self.tag_attr == TagAttr::Unquoted {
+               self.tag_attr = TagAttr::Name;
+            }
+
+            let mut pos = pos_start + 1;
+            while pos < pos_max && matches!(src[pos], 0x20 | 0x09) {
+               pos += 1;
+            }
+
+            return self.tag_attr_run(TokenBody::WhiteSpace, pos_start, pos, line);
+         }
+
+         0x3E /* > */ => {
+            self.tag_attr = TagAttr::Name;
+
+            let span = self.span_at(pos_start, 1, line);
+            return self.return_tokenized(Token::Real(TokenBody::TagOpenEnd(span)));
+         }
+
+         0x2F /* / */ if pos_start + 1 < pos_max
+            && src[pos_start + 1] == 0x3E /* > */
+         => {
+            self.tag_attr = TagAttr::Name;
+
+            let span = self.span_at(pos_start, 2, line);
+            return self.return_tokenized(Token::Real(TokenBody::TagSelfClose(span)));
+         }
+
+         0x3D /* = */ if self.tag_attr == TagAttr::Name => {
+            self.tag_attr = TagAttr::Value;
+
+            let span = self.span_at(pos_start, 1, line);
+            return self.return_tokenized(Token::Real(TokenBody::AttrEq(span)));
+         }
+
+         // Quote within unquoted value is a part of value.
+         0x22 /* " */ | 0x27 /* ' */ if self.tag_attr != TagAttr::Unquoted => {
+            self.tag_quote = byte;
+
+            let span = self.span_at(pos_start, 1, line);
+            return self.return_tokenized(Token::Real(TokenBody::AttrQuote(span)));
+         }
+
+         _ => {}
+      }
+
+      if self.tag_special_match(src, pos_start) {
+         return self.tag_special_tokenize(pos_start, line);
+      }
+
+      // This is attribute name or unquoted value. Value can not contain
+      // whitespace and ">", name stops at "=", quotes and "/>" as well.
+      let name = self.tag_attr == TagAttr::Name;
+      let mut pos = pos_start + 1;
+      while pos < pos_max {
+         let stop = match src[pos] {
+            0x0A | 0x20 | 0x09 | 0x0D | 0x3E => true,
+            0x3D /* = */ | 0x22 /* " */ | 0x27 /* ' */ => name,
+            0x2F /* / */ => name && pos + 1 < pos_max && src[pos + 1] == 0x3E,
+            _ => self.tag_special_match(src, pos),
+         };
+
+         if stop {
+            break;
+         }
+
+         pos += 1;
+      }
+
+      if name {
+         self.tag_attr_run(TokenBody::AttrName, pos_start, pos, line)
+      }
+      else {
+         self.tag_attr = TagAttr::Unquoted;
+         self.tag_attr_run(TokenBody::AttrValue, pos_start, pos, line)
+      }
+   }
+
+
+
+   // Tokenize quoted attribute value, after opening AttrQuote. Value is
+   // returned as AttrValue untill matching quote, newline, instruction or
+   // interpolation is found.
+   #[inline(always)]
+   fn tag_attr_quoted_tokenize(&mut self) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_start = self.pos_region;
+      let pos_max = src.len();
+      let quote = self.tag_quote;
+
+      let line = self.line;
+      let mut pos = pos_start;
+      while pos < pos_max {
+         let byte = src[pos];
+
+         if byte == quote {
+            self.tag_quote = 0;
+            self.tag_attr = TagAttr::Name;
+
+            let span = self.span_at(pos, 1, line);
+            return self.return_tokenized_after(TokenBody::AttrValue, pos_start, pos,
+               line, Token::Real(TokenBody::AttrQuote(span))
+            );
+         }
+
+         if byte == 0x0A /* newline */ || byte == 0x0D /* \r */ {
+            let span = self.span_at(pos, newline_len(src, pos), line);
+            return self.return_tokenized_after(TokenBody::AttrValue, pos_start, pos,
+               line, Token::Real(TokenBody::Newline(span))
+            );
+         }
+
+         if self.tag_special_match(src, pos) {
+            if pos > pos_start {
+               let span = self.span_at(pos_start, pos - pos_start, line);
+               return self.return_tokenized(Token::Real(TokenBody::AttrValue(span)));
+            }
+
+            return self.tag_special_tokenize(pos, line);
+         }
+
+         pos += 1;
+      }
+
+      self.tag_unclosed(TokenBody::AttrValue, pos_start, line)
+   }
+
+
+
+   // Return Token built with given body for bytes from pos_start till pos
+   // within tag. If it reaches the end of region, tag is not closed.
+   #[inline(always)]
+   fn tag_attr_run(&mut self, body: fn(Span) -> TokenBody, pos_start: usize,
+      pos: usize, line: usize
+   )
+      -> Option<Token>
+   {
+      if pos >= self.pos_max {
+         return self.tag_unclosed(body, pos_start, line);
+      }
+
+      let span = self.span_at(pos_start, pos - pos_start, line);
+      self.return_tokenized(Token::Real(body(span)))
+   }
+
+
+
+   // Returns true if instruction, template comment or interpolation starts at
+   // given position within tag.
+   #[inline(always)]
+   fn tag_special_match(&self, src: &[u8], pos: usize) -> bool {
+      let syntax = &self.syntax;
+      let byte = src[pos];
+
+      // Sigil and open delimiters are special in text as well, thus most of
+      // the bytes are refused with one lookup.
+      if !syntax.text_special.contains(byte) {
+         return false;
+      }
+
+      match byte {
+         byte if byte == syntax.sigil => true,
+         byte if byte == syntax.open_first || byte == syntax.raw_open_first => {
+            self.comment_match(src, pos)
+               || self.interpolation_match(src, pos, self.line, TextContext::Tag).is_some()
+         }
+         _ => false,
+      }
+   }
+
+
+
+   // Tokenize instruction, template comment or interpolation at given position
+   // within tag. This must be called only if tag_special_match returned true
+   // and there are no bytes between Tokenizer's position and pos.
+   #[inline(always)]
+   fn tag_special_tokenize(&mut self, pos: usize, line: usize) -> Option<Token> {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_max = src.len();
+
+      if src[pos] == self.syntax.sigil {
+         return self.instruction_tokenize(pos, pos, pos_max, line, line);
+      }
+
+      if self.comment_match(src, pos) {
+         return self.comment_open(pos, pos, line);
+      }
+
+      if let Some(body) = self.interpolation_match(src, pos, line, TextContext::Tag) {
+         // Interpolation right after "=" is an unquoted attribute value.
+         if self.tag_attr == TagAttr::Value {
+            self.tag_attr = TagAttr::Unquoted;
+         }
+
+         return self.return_tokenized(Token::Real(body));
+      }
+
+      Some(self.fail_token(Token::Fatal(ParseError::InternalError(Source {
+         pos_zero: self.pos_zero,
+         component: Component::Tokenizer,
+         line: line!(),
+         code: 0,
+      }))))
+   }
+
+
+
+   // Tag was not closed within current region. Return what's left as a Token
+   // built with given body and inform user that tag is not closed, the same way
+   // as it is done for instructions.
+   #[inline(always)]
+   fn tag_unclosed(&mut self, body: fn(Span) -> TokenBody, pos_start: usize,
+      line: usize
+   )
+      -> Option<Token>
+   {
+      if self.src_wait() {
+         return None;
+      }
+
+      let pos_max = self.pos_max;
+
+      if pos_start < pos_max {
+         let span = self.span_at(pos_start, pos_max - pos_start, line);
+
+         if let Err(token) = self.tokenbuf_push(Token::Real(body(span))) {
+            return Some(token);
+         }
       }
 
-      None
+      if let Err(token) = self.tokenbuf_push(Token::Error(
+         ParseError::OpenTag(Source {
+            pos_zero: self.pos_zero_prev_tag,
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 3,
+         })
+      )) {
+         return Some(token);
+      }
+
+      self.tag_quote = 0;
+      self.tag_attr = TagAttr::Name;
+      self.state = TokenizerState::ExpectDefered;
+      Some(Token::StateChange)
    }
 
 
@@ -768,33 +2840,72 @@ impl Tokenizer {
    )
       -> Option<Token>
    {
-      let src = &self.region[self.index];
+      let src = &self.region[self.index][..self.pos_max];
       let inf = pos_max + 1; // virtual infinity
 
       // This is just a guard for possible development bugs to be caught.
-      #[cfg(feature = "tokenizer_integrity_guard")] {
-         if src[pos_at] != 0x40 {
-            match self.fail_result_option_token(
+      #[cfg(not(feature = "unguarded_tokenizer_integrity"))] {
+         if src[pos_at] != self.syntax.sigil {
+            return Some(self.fail_token(
                Token::Fatal(ParseError::InternalError(Source {
                   pos_zero: self.pos_zero,
                   component: Component::Tokenizer,
                   line: line!(),
                   code: 0,
                }))
-            ) {
-               Ok(tok) => {
-                  return tok;
-               }
-               Err(tok) => {
-                  return Some(tok)
+            ));
+         }
+      }
+
+      // "@@" is an escaped @ symbol, it can never start an instruction. This
+      // allows user to write "user@@include.com" without any warnings.
+      if pos_at + 1 < pos_max && src[pos_at + 1] == self.syntax.sigil {
+         let span = self.span_at(pos_at, 2, line_at);
+         return self.return_tokenized_after(TokenBody::Defered, pos_start,
+            pos_at, line_start, Token::Real(TokenBody::EscapedAt(span))
+         );
+      }
+
+      // Trim marker is a part of instruction Token, i.e. "@-endif".
+      let pos_ident = ident_start(&src[..pos_max], pos_at);
+      let trim = pos_ident > pos_at + 1;
+
+      // Instructions without arguments are returned right away, since there is
+      // no parenthesis to look for. Such instruction name must follow @ symbol
+      // right away, i.e. "@endif", and @ symbol must not be a part of a word,
+      // i.e. "info@empty.org".
+      let pos_ident_end = ident_scan(&src[..pos_max], pos_ident) - 1;
+      if pos_ident_end >= pos_ident && !sigil_in_word(src, pos_at) {
+         let ident = ident_match(src, pos_ident, pos_ident_end);
+         let paren_next = pos_ident_end + 1 < pos_max
+            && src[pos_ident_end + 1] == 0x28 /* ( */;
+
+         let args = ident.args();
+         if let Some(body) = ident.body() {
+            if args == IdentArgs::None || (args == IdentArgs::Optional && !paren_next) {
+               let span = self.span_at(pos_at, pos_ident_end - pos_at + 1, line_at);
+               let tok = Token::Real(body(span));
+
+               if trim {
+                  self.trim_right = Some((self.index, pos_ident_end + 1));
+
+                  if self.trim_state() {
+                     return self.return_tokenized_trim_after(pos_start, pos_at,
+                        line_start, tok
+                     );
+                  }
                }
+
+               return self.return_tokenized_after(TokenBody::Defered, pos_start,
+                  pos_at, line_start, tok
+               );
             }
          }
       }
 
       // Since pos was pointing to @ symbol when this function is called. Move
-      // position one unit forward.
-      let mut pos = pos_at + 1;
+      // position forward to instruction name.
+      let mut pos = pos_ident;
       let mut line = line_at;
 
       let mut pos_first_char = inf;
@@ -810,11 +2921,9 @@ impl Tokenizer {
       let mut pos_post_whitespace_start = inf;
       let mut pos_post_whitespace_end = inf;
 
-      // Normally parenthesis is at the same line where @ is. In case when open
-      // parenthesis is not found, pos_open_pare will be infinity.
-      let mut line_open_paren = line_at;
-
-      let mut pos_last_linestart = pos_start;
+      // Tokenization does not always start at the line start, i.e. when there
+      // is a tag before instruction in the same line.
+      let mut pos_last_linestart = self.pos_region - self.pos_line;
 
       // At first we try to match all possible characters as instruction name.
       // Yes, this is slower than targeting to matching exact instruction
@@ -832,7 +2941,7 @@ impl Tokenizer {
       // @@.
       while pos < pos_max {
          match src[pos] {
-            0x0A /* newline */ | 0x20 /* space */ | 0x09 /* tab */ => {
+            0x0A /* newline */ | 0x0D /* \r */ | 0x20 /* space */ | 0x09 /* tab */ => {
                if pos_pre_whitespace_start == inf {
                   pos_pre_whitespace_start = pos;
                }
@@ -852,7 +2961,7 @@ impl Tokenizer {
 
                // Only newline characters increase line number, but we want to
                // reuse code for whitespace calculations.
-               if src[pos] == 0x0A {
+               if newline_last(src, pos) {
                   line += 1;
                   // line starts after this character/byte.
                   pos_last_linestart = pos + 1;
@@ -863,7 +2972,8 @@ impl Tokenizer {
             // open parenthesis.
             0x28 /* ( */ => {
                pos_open_paren = pos;
-               line_open_paren = line;
+               // Normally parenthesis is at the same line where @ is.
+               let line_open_paren = line;
 
                if pos_open_paren < pos_close_paren {
                   return self.instruction_tokenize_correct_paren(pos_at,
@@ -887,6 +2997,12 @@ impl Tokenizer {
 
             0x41..=0x5A /* A-Z */ |
             0x61..=0x7A /* a-z */ => {
+               // Second word after @ symbol, i.e. "@include file", this can not
+               // be an instruction anymore.
+               if pos_post_whitespace_start != inf {
+                  break;
+               }
+
                if pos_first_char == inf {
                   #[cfg(feature = "dbg_tokenizer_verbose")]{
                      println!("got first char at: {}", pos);
@@ -910,20 +3026,32 @@ impl Tokenizer {
             //    decide when to give up. This is the most friendly action path
             //    from users perspective.
             //
-            // We will try to walk path 3.
-            chr => {
-               println!("bad char? 0x{:02X}, line_open_paren: {}", chr, line_open_paren);
+            // We walk path 2. @ symbol is common in text (e-mail addresses, CSS
+            // at-rules, etc.), thus trying further would swallow everything
+            // till the next "(" or even the whole rest of the region. Warnings
+            // with suggestions are given in instruction_tokenize_unfinished.
+            _chr => {
+               #[cfg(feature = "dbg_tokenizer_verbose")]{
+                  println!("bad char? 0x{:02X}", _chr);
+               }
                if pos_first_bad_char == inf {
                   pos_first_bad_char = pos;
                }
 
                pos_last_bad_char = pos;
+               break;
             }
          }
 
          pos += 1;
       }
 
+      // Instruction name or whitespace after it reaches the end of open
+      // region, thus it is not known yet if it is followed by "(".
+      if pos >= pos_max && self.src_wait() {
+         return None;
+      }
+
       // If instruction was correctly written, Tokenizer should not be here, but
       // in case if it is, give state data to instruction_tokenize_unfinished
       // function to do deeper analysis and output useful tokens for error
@@ -932,7 +3060,7 @@ impl Tokenizer {
          pos_first_char, pos_last_char, pos_close_paren, pos_open_paren,
          pos_first_bad_char, pos_last_bad_char, pos_pre_whitespace_start,
          pos_pre_whitespace_end, pos_post_whitespace_start,
-         pos_post_whitespace_end
+         pos_post_whitespace_end, line_at
       )
    }
 
@@ -943,11 +3071,16 @@ impl Tokenizer {
    // to do meaningful analysis. We use separate function so that code length
    // per function is reasonable (not too long).
    // This call should happen if:
-   // 1) user has forgotten to write open parenthesis, thus whole template
-   //    source is scanned.
+   // 1) user has forgotten to write open parenthesis, i.e. "@include x".
    // 2) user has started instruction at the end of a file, but did not finish
    //    it completeley, for example template ends with "@inclu" and nothing
    //    follows.
+   // 3) @ symbol is not an instruction at all, i.e. "user@example.com".
+   //
+   // In all cases UnescapedAt is returned and Tokenizer continues with bytes
+   // after @ symbol as usual. If the word after @ is an instruction or is
+   // close to one, a warning is returned as well. Otherwise there is no
+   // warning, since there are too many legit uses for @ symbol in text.
    #[inline(always)]
    #[allow(unused_variables)]
    fn instruction_tokenize_unfinished(&mut self,
@@ -956,17 +3089,37 @@ impl Tokenizer {
       pos_open_paren: usize, pos_first_bad_char: usize,
       pos_last_bad_char: usize, pos_pre_whitespace_start: usize,
       pos_pre_whitespace_end: usize, pos_post_whitespace_start: usize,
-      pos_post_whitespace_end: usize
+      pos_post_whitespace_end: usize, line_at: usize
    ) -> Option<Token>{
+      let src = &self.region[self.index][..self.pos_max];
 
-      // TODO: insert warning tokens with user friendly messages and suggestions
-      // into tokenbuf.
+      // Instruction that requires arguments is written correctly, but there
+      // is no open parenthesis. There is no warning for @ symbol within a word,
+      // i.e. "info@include.lv" is most probably an e-mail address.
+      let warning = if sigil_in_word(src, pos_at) {
+         None
+      }
+      else if pos_first_char == ident_start(src, pos_at)
+      && ident_match(src, pos_first_char, pos_last_char).args()
+         == IdentArgs::Required
+      {
+         Some(ParseError::InstructionNotOpen(Source {
+            pos_zero: self.pos_zero + (pos_at - self.pos_region),
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 9,
+         }))
+      }
+      else if pos_first_char != inf {
+         self.instruction_near_miss(pos_at, pos_first_char, false)
+      }
+      else {
+         None
+      };
 
-      self.return_tokenized(Token::Real(TokenBody::Defered(Span {
-         index: self.index, line: self.line, pos_line: self.pos_line,
-         pos_region: self.pos_region, pos_zero: self.pos_zero,
-         length: pos_max - pos_start
-      })))
+      self.instruction_tokenize_unescaped_at(pos_at, pos_start, line_at,
+         warning
+      )
    }
 
 
@@ -1029,8 +3182,6 @@ impl Tokenizer {
       pos_last_bad_char: usize, line_at: usize, line_start: usize,
       line_open_paren: usize, pos_last_linestart: usize
    ) -> Option<Token>{
-      use Ident as I;
-
       let len_left = pos_start - self.pos_region;
       let pos_zero = self.pos_zero + len_left;
 
@@ -1058,18 +3209,24 @@ impl Tokenizer {
          }
       }
 
-      let src = &self.region[self.index];
+      let src = &self.region[self.index][..self.pos_max];
       #[cfg(feature = "dbg_tokenizer_verbose")]{
          println!("got open ( at pos_at: {}", pos_at);
       }
 
-      if (pos_at + 1) == pos_first_char {
-         match ident_match(src, pos_first_char, pos_last_char) {
-            I::Include(ident_pos_start, ident_pos_end) => {
+      if ident_start(src, pos_at) == pos_first_char {
+         let ident = ident_match(src, pos_first_char, pos_last_char);
+
+         match (ident.pos(), ident.body()) {
+            (Some((ident_pos_start, ident_pos_end)), Some(body)) => {
                #[cfg(feature = "dbg_tokenizer_verbose")]{
-                  println!("got @include {}, {}", ident_pos_start, ident_pos_end);
+                  println!("got {:?} {}, {}", ident, ident_pos_start, ident_pos_end);
                }
 
+               // Whitespace after closing parenthesis is trimmed, if
+               // instruction has trim marker, i.e. "@-if(".
+               self.trim_instr = pos_first_char > pos_at + 1;
+
                // If pos_at is somewhere further than Tokenizers pos_start, this
                // means that there is a defered token that must be returned before
                // instrution token is. This behavior is necessary so that in case
@@ -1082,7 +3239,7 @@ impl Tokenizer {
                   // Buffer all tokens that were matched regarding this instruction.
                   self.instruction_tokenize_correct_paren_defered(pos_at, pos_start,
                      pos_open_paren, ident_pos_end, line_at, line_start,
-                     line_open_paren, pos_last_linestart
+                     line_open_paren, pos_last_linestart, body
                   )
                }
                else {
@@ -1090,12 +3247,22 @@ impl Tokenizer {
                   // but return @include token right away.
                   self.instruction_tokenize_correct_paren_now(pos_at, pos_start,
                      pos_open_paren, ident_pos_end, line_at, line_start,
-                     line_open_paren, pos_last_linestart
+                     line_open_paren, pos_last_linestart, body
                   )
                }
             }
-            I::None => {
-               None
+            _ => {
+               // This is not a known instruction, thus @ symbol was not
+               // escaped. Based on DD-2023-07-07-01 return UnescapedAt. Since
+               // parenthesis follow, this looks like an instruction, thus user
+               // is warned with a suggestion.
+               let warning = self.instruction_near_miss(pos_at,
+                  pos_first_char, true
+               );
+
+               self.instruction_tokenize_unescaped_at(pos_at, pos_start,
+                  line_at, warning
+               )
             }
          }
       }
@@ -1124,7 +3291,8 @@ impl Tokenizer {
    fn instruction_tokenize_correct_paren_defered(&mut self,
       pos_at: usize, pos_start: usize, pos_open_paren: usize,
       ident_pos_end: usize, line_at: usize, line_start: usize,
-      line_open_paren: usize, pos_last_linestart: usize
+      line_open_paren: usize, pos_last_linestart: usize,
+      body: fn(Span) -> TokenBody
    )
       -> Option<Token>
    {
@@ -1164,7 +3332,32 @@ impl Tokenizer {
       let len_ident = ident_pos_end - pos_at + 1;
       let mut len_to_span = len_defered;
 
-      if let Err(token) = self.tokenbuf_push(Token::Real(TokenBody::Include(
+      // Whitespace before instruction with trim marker is returned as Phantom
+      // after Defered, or instead of it, if there is nothing else before
+      // instruction. See return_tokenized_trim_after.
+      let mut pos_trim = pos_at;
+      let mut trim_token = None;
+      if self.trim_instr && self.trim_state() {
+         pos_trim = self.trim_left_start(pos_start, pos_at);
+
+         if pos_trim < pos_at {
+            let token = self.trim_left_token(pos_trim, pos_at, line_start);
+            if pos_trim > pos_start {
+               if let Err(token) = self.tokenbuf_push(token) {
+                  return Some(token);
+               }
+            }
+            else {
+               trim_token = Some(token);
+            }
+         }
+      }
+
+      // This is used for OpenInstruction error, if instruction will not be
+      // closed.
+      self.pos_zero_prev_instr = self.pos_zero + len_to_span;
+
+      if let Err(token) = self.tokenbuf_push(Token::Real(body(
          Span {
             index: self.index,
             pos_region: pos_at,
@@ -1181,14 +3374,26 @@ impl Tokenizer {
       len_to_span += len_ident;
 
       let len_whitespace = pos_open_paren - ident_pos_end - 1;
-      if len_whitespace > 0 {
+      if line_at != line_open_paren {
+         // Whitespace contains newlines, thus it must be split into WhiteSpace
+         // and Newline tokens.
+         if let Some(error_token) = self.whitespace_into_tokenbuf(self.index,
+             pos_next_span, len_whitespace, line_at, line_open_paren
+         ) {
+            return Some(error_token);
+         }
+
+         pos_next_span += len_whitespace;
+         len_to_span += len_whitespace;
+      }
+      else if len_whitespace > 0 {
          if let Err(token) = self.tokenbuf_push(Token::Real(
             TokenBody::WhiteSpace(Span {
                index: self.index,
                pos_region: pos_next_span,
                pos_zero: self.pos_zero + len_to_span,
                pos_line: self.pos_line + len_to_span,
-               line: line_at, // TODO: is this correct?
+               line: line_at,
                length: len_whitespace,
             })
          )){
@@ -1214,18 +3419,16 @@ impl Tokenizer {
          return Some(token);
       }
 
-      // After tokens shall be consumed, further tokenization has to be
-      // in state ExpectDefered.
+      // Tokenizer state is not changed here. It is changed when buffered
+      // instruction Token is returned (see return_tokenized), otherwise the
+      // state that was active before instruction would be lost.
+      // The calling code can change mode as necessary. For example, @include
+      // instruction would require to parse contents as file path, @if would
+      // require to parse code as conditional, etc.
 
-      // At this point parenthesis have been already been parsed and
-      // buffered. By default Tokenizer does not know the context of
-      // these parenthesis, thus it swithces to default mode. The
-      // calling code can change mode as necessary.
-      // For example, @include instruction would require to parse
-      // contents as file path, @if would require to parse code as
-      // conditional, etc. At some instances maybe it is even
-      // forbidden to parse matched instruction in any special way.
-      self.state = TokenizerState::ExpectDefered;
+      if let Some(token) = trim_token {
+         return self.return_tokenized(token);
+      }
 
       // Return defered token and allow further calls to next to consume
       // token buffer.
@@ -1235,7 +3438,7 @@ impl Tokenizer {
          pos_zero: self.pos_zero,
          pos_line: self.pos_line,
          line: line_start,
-         length: len_defered
+         length: pos_trim - pos_start
       })))
    }
 
@@ -1248,7 +3451,8 @@ impl Tokenizer {
    fn instruction_tokenize_correct_paren_now(&mut self,
       pos_at: usize, pos_start: usize, pos_open_paren: usize,
       ident_pos_end: usize, line_at: usize, _line_start: usize,
-      line_open_paren: usize, pos_last_linestart: usize
+      line_open_paren: usize, pos_last_linestart: usize,
+      body: fn(Span) -> TokenBody
    )
       -> Option<Token>
    {
@@ -1299,7 +3503,11 @@ impl Tokenizer {
          return Some(token);
       }
 
-      self.return_tokenized(Token::Real(TokenBody::Include(Span {
+      // This is used for OpenInstruction error, if instruction will not be
+      // closed.
+      self.pos_zero_prev_instr = self.pos_zero;
+
+      self.return_tokenized(Token::Real(body(Span {
          index: self.index,
          pos_region: pos_start,
          pos_zero: self.pos_zero,
@@ -1321,15 +3529,73 @@ impl Tokenizer {
    )
       -> Option<Token>
    {
-      // TODO: here we should match on possible identifier and return better
-      // error/warning tokens; for now we give no extra information.
+      // Based on DD-2023-07-07-01 return UnespacedAt. Since there is an open
+      // parenthesis after the word, user most probably wanted to write an
+      // instruction, thus warn with a suggestion.
+      let warning = self.instruction_near_miss(_pos_at, _pos_first_char, true);
 
-      // Based on DD-2023-07-07-01 return UnespacedAt.
+      self.instruction_tokenize_unescaped_at(_pos_at, _pos_start, _line_at,
+         warning
+      )
+   }
 
-      self.return_tokenized(Token::Real(TokenBody::UnescapedAt(Span {
-         index: self.index, length: 1, pos_region: self.pos_region,
-         pos_line: self.pos_line, pos_zero: self.pos_zero, line: self.line
-      })))
+
+
+   // Build InstructionNearMiss warning for a word that starts at pos_word and
+   // follows @ symbol at pos_at. If the word is an instruction name or is close
+   // to one, instruction name is suggested. Otherwise escaping @ symbol is
+   // suggested if escape is true, or there is no warning.
+   #[inline(always)]
+   fn instruction_near_miss(&self, pos_at: usize, pos_word: usize, escape: bool)
+      -> Option<ParseError>
+   {
+      let src = &self.region[self.index][..self.pos_max];
+      let pos_word_end = ident_scan(src, pos_word);
+
+      let suggestion = if pos_word_end > pos_word {
+         ident_suggest(src, pos_word, pos_word_end - 1)
+      }
+      else {
+         None
+      };
+
+      let suggestion = match suggestion {
+         Some(name) => Suggestion::Instruction(name),
+         None if escape => Suggestion::EscapeAt,
+         None => return None,
+      };
+
+      Some(ParseError::InstructionNearMiss(Source {
+         pos_zero: self.pos_zero + (pos_at - self.pos_region),
+         component: Component::Tokenizer,
+         line: line!(),
+         code: 8,
+      }, suggestion))
+   }
+
+
+
+   // Return UnescapedAt for @ symbol at pos_at. If there are bytes between
+   // Tokenizer's position and pos_at, they are returned as Defered before.
+   // If warning is given, it is returned right after UnescapedAt.
+   #[inline(always)]
+   fn instruction_tokenize_unescaped_at(&mut self, pos_at: usize,
+      pos_start: usize, line_at: usize, warning: Option<ParseError>
+   )
+      -> Option<Token>
+   {
+      let span = self.span_at(pos_at, 1, line_at);
+      let tok = Token::Real(TokenBody::UnescapedAt(span));
+
+      if let Some(warning) = warning {
+         return self.return_tokenized_warn_after(pos_start, pos_at, line_at,
+            tok, Token::Warning(warning)
+         );
+      }
+
+      self.return_tokenized_after(TokenBody::Defered, pos_start, pos_at,
+         line_at, tok
+      )
    }
 
 
@@ -1341,8 +3607,12 @@ impl Tokenizer {
    // CloseParen tokens are returned.
    #[inline(always)]
    fn tokenize_instruction_args(&mut self) -> Option<Token> {
-      let src = &self.region[self.index];
+      let src = &self.region[self.index][..self.pos_max];
       let pos_max = src.len();
+      let cnt_openparen = self.cnt_openparen;
+      let cnt_closeparen = self.cnt_closeparen;
+      let sigil = self.syntax.sigil;
+      let special = &self.syntax.args_special;
 
       // TODO: check pos_line < pos_region, panic! if not, behind feature flag.
 
@@ -1352,49 +3622,90 @@ impl Tokenizer {
       let mut line = self.line;
       let mut pos = self.pos_region;
       while pos < pos_max {
-         match src[pos] {
-            0x0A /* newline */ => {
-               let pos_in_line = pos_token_start - pos_line_start;
-               let len_defered = pos - pos_token_start;
-               let len_prev_token = pos_token_start - self.pos_region;
-
-               if let Err(token) = self.tokenbuf.append(Token::Real(
-                  TokenBody::Defered(Span {
-                     index: self.index,
-                     pos_region: pos_token_start,
-                     pos_zero: self.pos_zero + len_prev_token,
-                     pos_line: pos_in_line,
-                     line: line,
-                     length: len_defered,
-                  })
-               )){
-                  return Some(token);
-               };
+         // Same as in defered_tokenize, jump right to the next special byte.
+         pos = special.find(src, pos);
+         if pos >= pos_max {
+            break;
+         }
 
-               if let Err(token) = self.tokenbuf.append(Token::Real(
-                  TokenBody::Newline(Span {
-                     index: self.index,
-                     pos_region: pos,
-                     pos_zero: self.pos_zero + len_prev_token + len_defered,
-                     pos_line: pos_in_line + len_defered,
-                     line: line,
-                     length: 1,
-                  })
-               )){
+         match src[pos] {
+            0x0A /* newline */ | 0x0D /* \r */ => {
+               let len_newline = newline_len(src, pos);
+               if let Err(token) = tokenizer_args_push(&mut self.tokenbuf,
+                  self.index, self.pos_region, self.pos_zero, pos_line_start,
+                  pos_token_start, pos, line, TokenBody::Newline, len_newline
+               ) {
                   return Some(token);
-               };
+               }
 
                // Same as in defered_tokenize.
                line += 1;
-               pos_line_start = pos + 1;
-               pos_token_start = pos + 1;
+               pos += len_newline;
+               pos_line_start = pos;
+               pos_token_start = pos;
+               continue;
             }
 
-            // TODO: At the moment we ignore @ symbols, since we do not care
-            // about them. What we could do is - warn if there are unescaped @
-            // symbols? Actually we must handle @symbol escaping, since from
-            // user's prespective it would be better to have same behavior
-            // everywhere?
+            // String literals are returned as separate tokens, so that
+            // parenthesis within them are not counted, i.e. "@if(x == ')')".
+            // Backslash escapes the next byte. String literal can not span
+            // over multiple lines, thus if it is not closed before newline,
+            // only the rest of the line is taken as unterminated string and
+            // user gets an error, instead of swallowing the rest of the file.
+            0x22 /* " */ | 0x27 /* ' */ => {
+               let quote = src[pos];
+               let mut closed = false;
+               let mut pos_end = pos + 1;
+               while pos_end < pos_max {
+                  match src[pos_end] {
+                     0x0A /* newline */ | 0x0D /* \r */ => {
+                        break;
+                     }
+                     0x5C /* \ */ if pos_end + 1 < pos_max
+                        && src[pos_end + 1] != 0x0A /* newline */
+                        && src[pos_end + 1] != 0x0D /* \r */
+                     => {
+                        pos_end += 2;
+                        continue;
+                     }
+                     byte if byte == quote => {
+                        pos_end += 1;
+                        closed = true;
+                        break;
+                     }
+                     _ => { }
+                  }
+
+                  pos_end += 1;
+               }
+
+               if let Err(token) = tokenizer_args_push(&mut self.tokenbuf,
+                  self.index, self.pos_region, self.pos_zero, pos_line_start,
+                  pos_token_start, pos, line, TokenBody::StringLiteral,
+                  pos_end - pos
+               ) {
+                  return Some(token);
+               }
+
+               if !closed {
+                  // src is still borrowed here, thus tokenbuf_push can not be
+                  // called, but Tokenizer fails the same way, if it fails.
+                  if let Err(token) = self.tokenbuf.push_back(Token::Error(
+                     ParseError::OpenString(Source {
+                        pos_zero: self.pos_zero + (pos - self.pos_region),
+                        component: Component::Tokenizer,
+                        line: line!(),
+                        code: 7,
+                     })
+                  )) {
+                     return Some(self.fail_token(token));
+                  }
+               }
+
+               pos = pos_end;
+               pos_token_start = pos;
+               continue;
+            }
 
             0x28 /* ( */ => {
                self.cnt_openparen += 1;
@@ -1407,19 +3718,27 @@ impl Tokenizer {
                   let len_defered = pos - pos_token_start;
                   let len_prev_token = pos_token_start - self.pos_region;
 
-                  self.state = TokenizerState::ExpectDefered;
+                  self.state = self.state_prev_instr;
 
-                  if len_defered > 0 {
-                     if let Err(token) = self.tokenbuf_push(Token::Real(TokenBody::Defered(Span {
-                        index: self.index,
-                        pos_region: pos_token_start,
-                        pos_zero: self.pos_zero + len_prev_token,
-                        pos_line: pos_in_line,
-                        line: line,
-                        length: len_defered,
-                     }))) {
-                        return Some(token);
-                     };
+                  if self.trim_instr {
+                     self.trim_right = Some((self.index, pos + 1));
+                  }
+
+                  // If there are buffered tokens (Newline, EscapedAt) before
+                  // CloseParen, it can not be returned right away.
+                  if len_defered > 0 || self.tokenbuf.num_tokens() > 0 {
+                     if len_defered > 0 {
+                        if let Err(token) = self.tokenbuf_push(Token::Real(TokenBody::Defered(Span {
+                           index: self.index,
+                           pos_region: pos_token_start,
+                           pos_zero: self.pos_zero + len_prev_token,
+                           pos_line: pos_in_line,
+                           line: line,
+                           length: len_defered,
+                        }))) {
+                           return Some(token);
+                        };
+                     }
 
                      if let Err(token) = self.tokenbuf_push(Token::Real(TokenBody::CloseParen(Span {
                         index: self.index,
@@ -1448,6 +3767,25 @@ impl Tokenizer {
                   }
                }
             }
+
+            // From user's prespective it is better to have the same @ symbol
+            // escaping behavior everywhere, thus "@@" is EscapedAt here as
+            // well. Single @ symbol has no special meaning within arguments.
+            // TODO: What we could do is - warn if there are unescaped @
+            // symbols? Sigil is configurable, see Syntax, it can not be a
+            // quote or parenthesis, thus it is matched last.
+            byte if byte == sigil && pos + 1 < pos_max && src[pos + 1] == sigil => {
+               if let Err(token) = tokenizer_args_push(&mut self.tokenbuf,
+                  self.index, self.pos_region, self.pos_zero, pos_line_start,
+                  pos_token_start, pos, line, TokenBody::EscapedAt, 2
+               ) {
+                  return Some(token);
+               }
+
+               pos += 2;
+               pos_token_start = pos;
+               continue;
+            }
             _ch => {
                #[cfg(feature = "dbg_tokenizer_verbose")]{
                   println!("non-special char pos: {}, char: 0x{:02X}, do nothing", pos, _ch);
@@ -1458,6 +3796,15 @@ impl Tokenizer {
          pos += 1;
       }
 
+      // Arguments can be closed by bytes that are not appended yet. Forget
+      // what was buffered and counted, it will be tokenized again.
+      if self.src_wait() {
+         self.tokenbuf.clear();
+         self.cnt_openparen = cnt_openparen;
+         self.cnt_closeparen = cnt_closeparen;
+         return None;
+      }
+
       // Being here means that pos == pos_max. Code should not reach this point
       // unless there are no matching closing parenthesis. We do not try to
       // detect if any tokens are buffered. We just buffer more and return
@@ -1492,7 +3839,7 @@ impl Tokenizer {
          return Some(token);
       };
 
-      self.state = TokenizerState::ExpectDefered;
+      self.state = self.state_prev_instr;
       return Some(Token::StateChange);
    }
 
@@ -1618,28 +3965,102 @@ impl Tokenizer {
          self.pos_zero = pos_zero + len_token;
          self.pos_line = pos_line + len_token;
 
+         if self.pos_region > self.pos_utf8_invalid {
+            self.src_utf8_report(pos_region, pos_zero);
+         }
+
          self.line = line;
          match tok {
             Token::Real(body)
             | Token::Phantom(body)
             => match body {
-               TokenBody::Newline(..) => {
+               TokenBody::Newline(span) => {
                   self.line = line + 1;
                   self.pos_line = 0;
+                  self.newline_style_check(span);
                }
-               TokenBody::Include(span) => {
-                  // switch into ExpectInstructionClose right away when instruction
-                  // with expected partenthesis is returned. This is easier to
-                  // implement, rather than switching into this state when
-                  // OpenParen is returned.
-                  // We can change this in future, if necessary.
+               TokenBody::OpenParen(..) => {
+                  // OpenParen is returned only after instruction that has
+                  // arguments, thus switch into ExpectInstructionClose. Since
+                  // there are many instructions, this is easier than switching
+                  // state on every instruction Token.
+                  self.state_prev_instr = self.state;
                   self.state = TokenizerState::ExpectInstructionClose;
-                  self.cnt_openparen = 0;
+                  self.cnt_openparen = 1;
                   self.cnt_closeparen = 0;
-                  self.pos_zero_prev_instr = span.pos_zero;
                }
-               TokenBody::OpenParen(..) => {
-                  self.cnt_openparen += 1;
+               TokenBody::TagOpenStart(span) => {
+                  self.raw_text = tag_raw_text(&self.region[self.index],
+                     span.pos_region + 1, span.pos_region + span.length - 1
+                  );
+                  self.state = TokenizerState::ExpectTagOpenEnd;
+                  self.pos_zero_prev_tag = span.pos_zero;
+                  self.tag_quote = 0;
+                  self.tag_attr = TagAttr::Name;
+               }
+               TokenBody::TagCloseStart(span) => {
+                  self.state = TokenizerState::ExpectTagCloseEnd;
+                  self.pos_zero_prev_tag = span.pos_zero;
+                  self.tag_quote = 0;
+               }
+               TokenBody::TagOpenEnd(..) => {
+                  self.state = if self.raw_text == TextContext::Text {
+                     TokenizerState::ExpectDefered
+                  }
+                  else {
+                     TokenizerState::ExpectRawTextEnd
+                  };
+               }
+               TokenBody::TagClose(..) | TokenBody::TagSelfClose(..) => {
+                  self.state = TokenizerState::ExpectDefered;
+               }
+               TokenBody::Doctype(span) => {
+                  // Declaration contents are tokenized as tag contents,
+                  // i.e. quoted public identifiers.
+                  self.raw_text = TextContext::Text;
+                  self.state = TokenizerState::ExpectTagOpenEnd;
+                  self.pos_zero_prev_tag = span.pos_zero;
+                  self.tag_quote = 0;
+                  self.tag_attr = TagAttr::Name;
+               }
+               TokenBody::HtmlCommentOpen(span) => {
+                  self.state_prev_markup = self.state;
+                  self.state = TokenizerState::ExpectHtmlCommentClose;
+                  self.pos_zero_prev_markup = span.pos_zero;
+               }
+               TokenBody::CdataOpen(span) => {
+                  self.state_prev_markup = self.state;
+                  self.state = TokenizerState::ExpectCdataClose;
+                  self.pos_zero_prev_markup = span.pos_zero;
+               }
+               TokenBody::HtmlCommentClose(..)
+               | TokenBody::CdataClose(..) => {
+                  self.state = self.state_prev_markup;
+               }
+               TokenBody::InterpolationOpen(span, _) => {
+                  self.state_prev_interp = self.state;
+                  self.state = TokenizerState::ExpectInterpolationClose;
+                  self.pos_zero_prev_interp = span.pos_zero;
+               }
+               TokenBody::RawInterpolationOpen(span, _) => {
+                  self.state_prev_interp = self.state;
+                  self.state = TokenizerState::ExpectRawInterpolationClose;
+                  self.pos_zero_prev_interp = span.pos_zero;
+               }
+               TokenBody::InterpolationClose(..)
+               | TokenBody::RawInterpolationClose(..) => {
+                  self.state = self.state_prev_interp;
+               }
+               TokenBody::Verbatim(span) => {
+                  self.state_prev_verbatim = self.state;
+                  self.state = TokenizerState::ExpectVerbatimEnd;
+                  self.pos_zero_prev_verbatim = span.pos_zero;
+               }
+               TokenBody::EndVerbatim(..) => {
+                  // "@endverbatim" without "@verbatim" does not change state.
+                  if self.state == TokenizerState::ExpectVerbatimEnd {
+                     self.state = self.state_prev_verbatim;
+                  }
                }
                _ => {}
             }
@@ -1678,24 +4099,38 @@ impl Tokenizer {
             )));
          }
 
-         // If this was last token in current region.
-         if self.pos_max == self.pos_region {
+         // If this was last token in current region. Open region does not end
+         // untill src_close.
+         if self.pos_max == self.pos_region && !self.src_wait() {
             // If this is the "root" region, there is no place to fall back.
             if self.index == 0 {
                return Some(tok);
             }
 
             if let Some(snap) = self.state_snap.pop(){
-               self.index -= 1;
+               // Region is tokenized completely, including regions included
+               // from it, thus from now on it can be released.
+               self.region_meta[self.index].pos_zero_end = self.pos_zero;
+
+               // Region is included from snap.index, which is not necessarily
+               // the previous one, i.e. when the same region has two includes.
+               #[cfg(not(feature = "unguarded_tokenizer_integrity"))]
+               let index_child = self.index;
+               self.index = snap.index;
                self.pos_region = snap.pos_region;
                self.pos_line = snap.pos_line;
                self.line = snap.line;
 
-               let src = &self.region[self.index];
-               self.pos_max = src.len();
+               self.pos_max = if self.src_wait() {
+                  self.pos_max_open
+               }
+               else {
+                  self.region[self.index].len()
+               };
+               self.pos_utf8_invalid = self.region_meta[self.index].pos_utf8_invalid;
 
                #[cfg(not(feature = "unguarded_tokenizer_integrity"))] {
-                  if self.index != snap.index {
+                  if self.index >= index_child {
                      if let ParseError::InternalError(..) = self.parse_error_prev { }
                      else {
                         // We are already failing, if this fails as well, there
@@ -1766,36 +4201,93 @@ impl Tokenizer {
 
 
 
-   // It is not allowed to print anything in this function because it will be
-   // used from SpanFormatter trait impl, that will be called from
-   // std::fmt::Debug. This caught me by surprise once. It seems that
-   // println! writes in the same buffer as fmt::Debug? Maybe they both use
-   // stdout? But the weird part is that output texts overlay.
-   pub fn span_slice<'a>(&'a self, span: &'a Span) -> Option<&'a [u8]> {
+   // Returns source region for given Span, if Span can be sliced from it.
+   fn span_region(&self, span: &Span) -> Result<&[u8], Token> {
+      // Span is from previous generation, its region is gone or index
+      // belongs to other region now.
+      if span.pos_zero < self.pos_zero_generation {
+         return Err(Token::Error(ParseError::RegionReleased(Source {
+            pos_zero: span.pos_zero,
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 27,
+         })));
+      }
+
       // Someone has given us wrong Span. It is impossible to trigger
       // this error unless Span was constructed manually or there is a
       // bug in code.
-      if let TokenizerState::ExpectInput = self.state {
-         return None;
-      }
+      let meta = match self.region_meta.get(span.index) {
+         Some(meta) if self.state != TokenizerState::ExpectInput => meta,
+         _ => {
+            return Err(Token::Error(ParseError::InternalError(Source {
+               pos_zero: span.pos_zero,
+               component: Component::Tokenizer,
+               line: line!(),
+               code: 28,
+            })));
+         }
+      };
 
-      // Wrong Span (out of bounds).
-      if span.index > self.region.len() - 1 {
-         return None;
+      if meta.released {
+         return Err(Token::Error(ParseError::RegionReleased(Source {
+            pos_zero: span.pos_zero,
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 26,
+         })));
       }
 
-      let src = &self.region[span.index];
-      let inf = src.len() + 1;
+      Ok(&self.region[span.index])
+   }
 
-      // This implicitly checks for "span.src_pos >= inf" as well.
-      if span.pos_region + span.length >= inf {
-         return None;
+
+
+   /// Returns source bytes for given Span.
+   ///
+   /// # Return
+   ///
+   /// * 'Err(Token)' - RegionReleased, if Span points into region released
+   ///   with region_release or Span is from before reset; InternalError, if
+   ///   Span does not belong to this Tokenizer.
+   pub fn span_slice<'a>(&'a self, span: &Span) -> Result<&'a [u8], Token> {
+      // It is not allowed to print anything in this function because it will
+      // be used from SpanFormatter trait impl, that will be called from
+      // std::fmt::Debug. This caught me by surprise once. It seems that
+      // println! writes in the same buffer as fmt::Debug? Maybe they both use
+      // stdout? But the weird part is that output texts overlay.
+      let src = match self.span_region(span) {
+         Ok(src) => src,
+         Err(token) => return Err(token),
+      };
+
+      // Wrong Span (out of bounds).
+      if span.pos_region + span.length > src.len() {
+         return Err(Token::Error(ParseError::InternalError(Source {
+            pos_zero: span.pos_zero,
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 28,
+         })));
       }
 
       let start = span.pos_region;
       let end = span.pos_region + span.length;
 
-      Some(&src[start..end])
+      Ok(&src[start..end])
+   }
+
+
+
+   /// Returns UTF-8 character column and display width for given Span, see
+   /// SpanColumns. This is intended for diagnostics, where byte positions
+   /// point to the wrong column if line contains non-ASCII text. Returns None
+   /// for the same cases as span_slice returns error.
+   pub fn span_columns(&self, span: &Span) -> Option<SpanColumns> {
+      match self.span_region(span) {
+         Ok(src) => span_columns(src, span, self.tab_width),
+         Err(..) => None,
+      }
    }
 }
 
@@ -1879,6 +4371,21 @@ fn tokenlist_match_or_fail(t: &mut Tokenizer, list: &[Token], allow_unbuffered:
                (Pe::NoMemory(s1), Pe::NoMemory(s2))
                | (Pe::InternalError(s1), Pe::InternalError(s2))
                | (Pe::OpenInstruction(s1), Pe::OpenInstruction(s2))
+               | (Pe::OpenTag(s1), Pe::OpenTag(s2))
+               | (Pe::OpenInterpolation(s1), Pe::OpenInterpolation(s2))
+               | (Pe::OpenComment(s1), Pe::OpenComment(s2))
+               | (Pe::OpenVerbatim(s1), Pe::OpenVerbatim(s2))
+               | (Pe::OpenHtmlComment(s1), Pe::OpenHtmlComment(s2))
+               | (Pe::OpenCdata(s1), Pe::OpenCdata(s2))
+               | (Pe::StreamError(s1), Pe::StreamError(s2))
+               | (Pe::LookaheadError(s1), Pe::LookaheadError(s2))
+               | (Pe::RegionReleased(s1), Pe::RegionReleased(s2))
+               | (Pe::InvalidUtf8(s1), Pe::InvalidUtf8(s2))
+               | (Pe::MixedNewline(s1), Pe::MixedNewline(s2))
+               | (Pe::InvalidDelimiter(s1), Pe::InvalidDelimiter(s2))
+               | (Pe::OpenString(s1), Pe::OpenString(s2))
+               | (Pe::UnescapedLt(s1), Pe::UnescapedLt(s2))
+               | (Pe::UnescapedGt(s1), Pe::UnescapedGt(s2))
                | (Pe::InstructionError(s1), Pe::InstructionError(s2))
                | (Pe::InstructionNotOpen(s1), Pe::InstructionNotOpen(s2))
                | (Pe::InstructionMissingArgs(s1), Pe::InstructionMissingArgs(s2))
@@ -1891,6 +4398,26 @@ fn tokenlist_match_or_fail(t: &mut Tokenizer, list: &[Token], allow_unbuffered:
                      return Err((idx, Some((*expect).clone()), Some(token)));
                   }
                }
+               (Pe::InstructionNearMiss(s1, x1), Pe::InstructionNearMiss(s2, x2))
+               => {
+                  if s1.pos_zero != s2.pos_zero
+                  || s1.component != s2.component
+                  || s1.code != s2.code
+                  || x1 != x2
+                  {
+                     return Err((idx, Some((*expect).clone()), Some(token)));
+                  }
+               }
+               (Pe::ErrorLimit(s1, x1), Pe::ErrorLimit(s2, x2))
+               => {
+                  if s1.pos_zero != s2.pos_zero
+                  || s1.component != s2.component
+                  || s1.code != s2.code
+                  || x1 != x2
+                  {
+                     return Err((idx, Some((*expect).clone()), Some(token)));
+                  }
+               }
                _ => {
                   if *expect != token {
                      return Err((idx, Some((*expect).clone()), Some(token)));
@@ -1914,7 +4441,7 @@ fn tokenlist_match_or_fail(t: &mut Tokenizer, list: &[Token], allow_unbuffered:
       // tokenizer. This must be at the end of the iteration, before next token
       // is consumed, otherwize Tokenizer would build token from source.
       if !allow_unbuffered {
-         if t.tokenbuf.buf_len() < 1 {
+         if t.tokenbuf.num_tokens() < 1 {
             break;
          }
       }
@@ -1946,7 +4473,7 @@ fn tokenlist_match_or_fail(t: &mut Tokenizer, _: &[Token], allow_unbuffered: boo
    while let Some(token) = t.next() {
       println!("{:?}", token);
       if !allow_unbuffered {
-         if t.tokenbuf.buf_len() < 1 {
+         if t.tokenbuf.num_tokens() < 1 {
             break;
          }
       }
@@ -1972,6 +4499,57 @@ mod test_ident;
 #[cfg(test)]
 mod test_instruction;
 
+#[cfg(test)]
+mod test_tag;
+
+#[cfg(test)]
+mod test_interpolation;
+
+#[cfg(test)]
+mod test_escape;
+
+#[cfg(test)]
+mod test_comment;
+
+#[cfg(test)]
+mod test_verbatim;
+
+#[cfg(test)]
+mod test_raw_text;
+
+#[cfg(test)]
+mod test_markup;
+
+#[cfg(test)]
+mod test_stream;
+
+#[cfg(test)]
+mod test_utf8;
+
+#[cfg(test)]
+mod test_newline;
+
+#[cfg(test)]
+mod test_syntax;
+
+#[cfg(test)]
+mod test_scan;
+
+#[cfg(test)]
+mod test_limit;
+
+#[cfg(test)]
+mod test_trim;
+
+#[cfg(test)]
+mod test_lookahead;
+
+#[cfg(test)]
+mod test_reset;
+
+#[cfg(test)]
+mod test_release;
+
 
 
 // ================== EOF: do not write below this ============================
@@ -7,21 +7,13 @@ use crate::{
 
 
 
-impl SpanFormatter for Tokenizer {
+impl SpanFormatter for Tokenizer<'_> {
    fn fmt_into(&self, fmt: &mut std::fmt::Formatter, span: &Span) -> std::fmt::Result {
-      let text = if let Some(slice) = self.span_slice(span) {
-         match std::str::from_utf8(slice) {
-               Ok(s) => {
-                  let text = s.to_owned();
-                  Some(text)
-               }
-               Err(..) => {
-                  // TODO: what to do, return error?
-                  // maybe show as binary slice instead since it is not 
-                  // readable?
-                  None
-               }
-         }
+      let text = if let Ok(slice) = self.span_slice(span) {
+         // Invalid UTF-8 is reported by Tokenizer as InvalidUtf8 error, thus
+         // here it is enough to show replacement characters instead.
+         let text = String::from_utf8_lossy(slice).into_owned();
+         Some(text)
       }
       else {
          // TODO: IDK what could we write for text? Return error?
@@ -7,21 +7,213 @@
 // because it will be the same line number as for Ident symbol. In Ident sense
 // they are relative to current region being tokenized.
 //
+use crate::{
+   span::Span,
+   tokenbody::TokenBody,
+};
+
+
+
 #[derive(Debug)]
 pub(super) enum Ident {
    // This Ident is matched for "@include(", "@include  \n  (", but not for
    // "@   include(".
    Include(usize, usize),
+   Require(usize, usize),
+   Extends(usize, usize),
+   Section(usize, usize),
+   EndSection(usize, usize),
+   Yield(usize, usize),
+   If(usize, usize),
+   ElseIf(usize, usize),
+   Else(usize, usize),
+   EndIf(usize, usize),
+   Unless(usize, usize),
+   EndUnless(usize, usize),
+   Foreach(usize, usize),
+   EndForeach(usize, usize),
+   For(usize, usize),
+   EndFor(usize, usize),
+   While(usize, usize),
+   EndWhile(usize, usize),
+   Empty(usize, usize),
+   Break(usize, usize),
+   Continue(usize, usize),
+   Verbatim(usize, usize),
+   EndVerbatim(usize, usize),
+
+   // Slice is not matched as identifier. Use ident_suggest to find out if it
+   // is close to some identifier.
+   None
+}
 
-   // TODO: create AlmostInclude an Ident that has correct word, but bad 
-   // character case. We shall emit warning for those.
 
-   // TODO: create MaybeInclude an Ident that has some bad characters, but is
-   // very close to @include. Emit warning and suggestion how to fix it: either
-   // correct ident or escape @. This could match "@   include(" and friends.
 
-   // Slice is not matched as identifier.
-   None
+// Describes if instruction is followed by arguments in parenthesis.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum IdentArgs {
+   // Instruction must have arguments, i.e. "@include(file)".
+   Required,
+
+   // Instruction can be used with or without arguments, i.e. "@break" and
+   // "@break($x > 1)". Arguments are tokenized only if open parenthesis
+   // follows instruction name right away.
+   Optional,
+
+   // Instruction never has arguments, i.e. "@endif". Parenthesis that follow
+   // such instruction are not part of it.
+   None,
+}
+
+
+
+// Row for IDENT_TABLE: identifier name and Ident constructor.
+type IdentRow = (&'static str, fn(usize, usize) -> Ident);
+
+
+
+// Identifier table. To add a new identifier, add Ident and TokenBody variants
+// and a row in this table. Ident methods below must be extended as well, but
+// compiler will not let you forget that.
+const IDENT_TABLE: &[IdentRow] = &[
+   ("include", Ident::Include),
+   ("require", Ident::Require),
+   ("extends", Ident::Extends),
+   ("section", Ident::Section),
+   ("endsection", Ident::EndSection),
+   ("yield", Ident::Yield),
+   ("if", Ident::If),
+   ("elseif", Ident::ElseIf),
+   ("else", Ident::Else),
+   ("endif", Ident::EndIf),
+   ("unless", Ident::Unless),
+   ("endunless", Ident::EndUnless),
+   ("foreach", Ident::Foreach),
+   ("endforeach", Ident::EndForeach),
+   ("for", Ident::For),
+   ("endfor", Ident::EndFor),
+   ("while", Ident::While),
+   ("endwhile", Ident::EndWhile),
+   ("empty", Ident::Empty),
+   ("break", Ident::Break),
+   ("continue", Ident::Continue),
+   ("verbatim", Ident::Verbatim),
+   ("endverbatim", Ident::EndVerbatim),
+];
+
+
+
+impl Ident {
+   // Returns matched identifier position (pos_start, pos_end) or None if
+   // identifier was not matched.
+   pub(super) fn pos(&self) -> Option<(usize, usize)> {
+      use Ident as I;
+
+      match self {
+         I::Include(start, end)
+         | I::Require(start, end)
+         | I::Extends(start, end)
+         | I::Section(start, end)
+         | I::EndSection(start, end)
+         | I::Yield(start, end)
+         | I::If(start, end)
+         | I::ElseIf(start, end)
+         | I::Else(start, end)
+         | I::EndIf(start, end)
+         | I::Unless(start, end)
+         | I::EndUnless(start, end)
+         | I::Foreach(start, end)
+         | I::EndForeach(start, end)
+         | I::For(start, end)
+         | I::EndFor(start, end)
+         | I::While(start, end)
+         | I::EndWhile(start, end)
+         | I::Empty(start, end)
+         | I::Break(start, end)
+         | I::Continue(start, end)
+         | I::Verbatim(start, end)
+         | I::EndVerbatim(start, end)
+         => Some((*start, *end)),
+
+         I::None => None,
+      }
+   }
+
+
+
+   // Returns TokenBody constructor for matched identifier.
+   pub(super) fn body(&self) -> Option<fn(Span) -> TokenBody> {
+      use Ident as I;
+      use TokenBody as Tb;
+
+      let body: fn(Span) -> TokenBody = match self {
+         I::Include(..) => Tb::Include,
+         I::Require(..) => Tb::Require,
+         I::Extends(..) => Tb::Extends,
+         I::Section(..) => Tb::Section,
+         I::EndSection(..) => Tb::EndSection,
+         I::Yield(..) => Tb::Yield,
+         I::If(..) => Tb::If,
+         I::ElseIf(..) => Tb::ElseIf,
+         I::Else(..) => Tb::Else,
+         I::EndIf(..) => Tb::EndIf,
+         I::Unless(..) => Tb::Unless,
+         I::EndUnless(..) => Tb::EndUnless,
+         I::Foreach(..) => Tb::Foreach,
+         I::EndForeach(..) => Tb::EndForeach,
+         I::For(..) => Tb::For,
+         I::EndFor(..) => Tb::EndFor,
+         I::While(..) => Tb::While,
+         I::EndWhile(..) => Tb::EndWhile,
+         I::Empty(..) => Tb::Empty,
+         I::Break(..) => Tb::Break,
+         I::Continue(..) => Tb::Continue,
+         I::Verbatim(..) => Tb::Verbatim,
+         I::EndVerbatim(..) => Tb::EndVerbatim,
+         I::None => return None,
+      };
+
+      Some(body)
+   }
+
+
+
+   // Returns if identifier requires arguments.
+   pub(super) fn args(&self) -> IdentArgs {
+      use Ident as I;
+
+      match self {
+         I::Include(..)
+         | I::Require(..)
+         | I::Extends(..)
+         | I::Section(..)
+         | I::Yield(..)
+         | I::If(..)
+         | I::ElseIf(..)
+         | I::Unless(..)
+         | I::Foreach(..)
+         | I::For(..)
+         | I::While(..)
+         => IdentArgs::Required,
+
+         I::Empty(..)
+         | I::Break(..)
+         | I::Continue(..)
+         => IdentArgs::Optional,
+
+         I::EndSection(..)
+         | I::Else(..)
+         | I::EndIf(..)
+         | I::EndUnless(..)
+         | I::EndForeach(..)
+         | I::EndFor(..)
+         | I::EndWhile(..)
+         | I::Verbatim(..)
+         | I::EndVerbatim(..)
+         | I::None
+         => IdentArgs::None,
+      }
+   }
 }
 
 
@@ -36,57 +228,135 @@ pub(super) fn ident_match(src: &[u8], start: usize, end: usize) -> Ident {
    // Caller should ensure that ident_match is called with correct parameters.
    // From ident_match perspective, it is not possible to match identifier
    // whose end is after start.
-   if end < start {
+   if end < start || end >= src.len() {
       return Ident::None;
    }
 
-   let len = end - start + 1;
+   let ident = &src[start..end + 1];
 
-   if len == 7 {
-      return ident_match_7(src, start, end);
+   // Identifiers are short and there are not many of them, thus comparing
+   // lengths first is enough to make this fast. If table grows a lot, it
+   // could be sorted by length or first byte.
+   for (name, ident_new) in IDENT_TABLE {
+      if name.len() == ident.len() && name.as_bytes() == ident {
+         return ident_new(start, end);
+      }
    }
 
-   // TODO: implement identifier matching for other lengths and identifiers
-   // when available. Implement matching for almost-correct idents as well.
-   // For now we must move forward, thus poor-matching is implemented.
-
    Ident::None
 }
 
 
 
-// Identifier matching when there are exactly 7 bytes available.
+// Returns position right after the last ASCII letter in a run of letters that
+// starts at given position. If there is no letter at start, start is returned.
 #[inline(always)]
-fn ident_match_7(src: &[u8], start: usize, end: usize) -> Ident {
+pub(super) fn ident_scan(src: &[u8], start: usize) -> usize {
+   let mut pos = start;
+   while pos < src.len()
+   && matches!(src[pos], 0x41..=0x5A /* A-Z */ | 0x61..=0x7A /* a-z */)
+   {
+      pos += 1;
+   }
+
+   pos
+}
+
+
+
+// Function that tries to find identifier that is close to given slice, but
+// is not an exact match. This is intended for warnings, when ident_match has
+// returned None, i.e. "@Include(" has bad character case and "@inclde(" has a
+// typo. Returns identifier name that user most probably wanted to write.
+//
+// Typos are matched only for identifiers that have at least 4 bytes, because
+// otherwise too many words would be close to short identifiers like "if".
+// Typo is one missing, one extra, one different byte or two adjacent bytes
+// swapped.
+//
+// start - position (inclusive) in buffer, from where should the ident be matched
+// end - position (inclusive) in buffer, till which identifier should be matched.
+#[inline(always)]
+pub(super) fn ident_suggest(src: &[u8], start: usize, end: usize)
+   -> Option<&'static str>
+{
+   if end < start || end >= src.len() {
+      return None;
+   }
+
    let ident = &src[start..end + 1];
 
-   match ident[0] {
-      0x69 /* i */ => {
-         match ident[1] {
-            0x6E /* n */ => {
-               /* match 'clude' */
-               if ident[2] == 0x63 /* c */
-               && ident[3] == 0x6C /* l */
-               && ident[4] == 0x75 /* u */
-               && ident[5] == 0x64 /* d */
-               && ident[6] == 0x65 /* e */
-               {
-                  Ident::Include(start, end)
-               }
-               else {
-                  Ident::None
-               }
-            }
-            _ => {
-               Ident::None
+   for (name, _) in IDENT_TABLE {
+      if name.as_bytes().eq_ignore_ascii_case(ident) {
+         return Some(name);
+      }
+   }
+
+   if ident.len() < 3 {
+      return None;
+   }
+
+   IDENT_TABLE.iter()
+      .map(|(name, _)| *name)
+      .find(|name| name.len() >= 4 && ident_typo(ident, name.as_bytes()))
+}
+
+
+
+// Returns true if ident differs from name by one typo, see ident_suggest.
+// Character case is ignored.
+#[inline(always)]
+fn ident_typo(ident: &[u8], name: &[u8]) -> bool {
+   let eq = |a: u8, b: u8| a.eq_ignore_ascii_case(&b);
+
+   if ident.len() == name.len() {
+      let mut pos_diff = ident.len();
+      let mut cnt_diff = 0;
+
+      for pos in 0..ident.len() {
+         if !eq(ident[pos], name[pos]) {
+            if cnt_diff == 0 {
+               pos_diff = pos;
             }
+            cnt_diff += 1;
          }
       }
 
-      _ => {
-         Ident::None
+      // One different byte, i.e. "inclide".
+      if cnt_diff == 1 {
+         return true;
       }
+
+      // Two adjacent bytes swapped, i.e. "inlcude".
+      return cnt_diff == 2
+         && pos_diff + 1 < ident.len()
+         && eq(ident[pos_diff], name[pos_diff + 1])
+         && eq(ident[pos_diff + 1], name[pos_diff]);
    }
-}
 
+   // One extra byte in longer slice, i.e. "inclde" vs "include".
+   let (short, long) = if ident.len() + 1 == name.len() {
+      (ident, name)
+   }
+   else if name.len() + 1 == ident.len() {
+      (name, ident)
+   }
+   else {
+      return false;
+   };
+
+   let mut pos = 0;
+   while pos < short.len() && eq(short[pos], long[pos]) {
+      pos += 1;
+   }
 
+   // Skip one byte in longer slice, the rest must match.
+   while pos < short.len() {
+      if !eq(short[pos], long[pos + 1]) {
+         return false;
+      }
+      pos += 1;
+   }
+
+   true
+}
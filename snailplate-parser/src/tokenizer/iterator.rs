@@ -13,43 +13,106 @@ use crate::{
 
 
 
-impl Iterator for Tokenizer {
+impl Iterator for Tokenizer<'_> {
    type Item = Token;
 
    #[inline]
    fn next(&mut self) -> Option<Self::Item> {
+      if self.lookahead_pos < self.lookahead.len() {
+         return Some(self.lookahead_consume());
+      }
+
+      let token = self.token_next_limited();
+
+      if let Some(token) = &token {
+         if !self.checkpoint_live.is_empty() {
+            if let Err(etoken) = self.lookahead_push(token.clone()) {
+               return Some(etoken);
+            }
+            self.lookahead_pos += 1;
+         }
+         else {
+            // Token is not kept, but it is counted, thus lookahead_base is
+            // the number of all returned Tokens before lookahead[0].
+            self.lookahead_base += 1;
+         }
+      }
+
+      token
+   }
+}
+
+
+
+impl Tokenizer<'_> {
+   // Returns the next tokenized Token with error and warning limits applied.
+   // Errors and warnings are counted here, thus Tokens returned again after
+   // rewind are not counted twice.
+   #[inline(always)]
+   pub(super) fn token_next_limited(&mut self) -> Option<Token> {
+      let token = self.token_next();
+
+      // Errors and warnings are counted only when they leave tokenbuf, thus
+      // buffered ones do not exceed limit before it is their turn.
+      match token {
+         Some(Token::Error(..)) => {
+            self.cnt_error += 1;
+            if self.cnt_error > self.error_limit {
+               return Some(self.error_limit_fail(22));
+            }
+         }
+         Some(Token::Warning(..)) => {
+            self.cnt_warning += 1;
+            if self.cnt_warning > self.warning_limit {
+               return Some(self.error_limit_fail(23));
+            }
+         }
+         _ => {}
+      }
+
+      token
+   }
+
+
+
+   // Returns the next Token for Iterator, before error and warning limits are
+   // applied.
+   #[inline(always)]
+   fn token_next(&mut self) -> Option<Token> {
       use TokenizerState as Ts;
 
+      // Error for invalid UTF-8 byte is returned right after Token, that
+      // envelops it, even if there are more buffered Tokens.
+      if let Some(error) = self.utf8_error.take() {
+         return Some(Token::Error(error));
+      }
+
+      // The same goes for line ending warning, it follows Newline.
+      if let Some(warning) = self.newline_warning.take() {
+         return Some(Token::Warning(warning));
+      }
+
       // We allow to consume tokenbuf even if Tokenizer is in failed state. This
       // is so that user can receive all warning/error tokens up to the point
       // where Tokenizer failed.
-      if self.tokenbuf.num_tokens() > 0 {
-         match self.tokenbuf_consume() {
-            Ok(tok) => {
-               if let Some(tok) = tok {
-                  // If we have consumed correct buffered token, just return it
-                  // to the caller.
-                  #[cfg(feature = "dbg_tokenbuf_verbose")] {
-                     println!("Tokenizer: return buffered token: {:?}", tok);
-                  }
-                  return self.return_tokenized(tok);
-               }
-               else {
-                  #[cfg(feature = "dbg_tokenbuf_verbose")] {
-                     println!("Tokenizer(WARN): buffered token not found!");
-                  }
-
-                  /* That's okay. Tokenbuf has been completeley consumed. */
-               }
-            }
+      if let Some(tok) = self.tokenbuf_consume() {
+         // If we have consumed buffered token, just return it to the caller.
+         #[cfg(feature = "dbg_tokenbuf_verbose")] {
+            println!("Tokenizer: return buffered token: {:?}", tok);
+         }
+         return self.return_tokenized(tok);
+      }
 
-            Err(etok) => {
-               // It is expected that tokenbuf_consume, if there is an error,
-               // leaves Tokenizer in such a state, that even if it is used in
-               // Iterator/loop, None is returned on next call. Thus we should
-               // not worry about infinite looping here.
-               return Some(etok);
-            }
+      // Whitespace after Token with trim marker is tokenized before text. It
+      // is done here, not within text tokenization functions, so that they
+      // are not re-entered. Within tags whitespace is a part of tag syntax.
+      if self.trim_right == Some((self.index, self.pos_region))
+      && matches!(self.state, Ts::ExpectDefered | Ts::ExpectRawTextEnd
+         | Ts::ExpectHtmlCommentClose | Ts::ExpectCdataClose)
+      {
+         let token = self.trim_right_tokenize();
+         if token.is_some() || self.trim_right.is_some() {
+            return token;
          }
       }
 
@@ -60,6 +123,29 @@ impl Iterator for Tokenizer {
          Ts::ExpectInstructionClose => {
             self.tokenize_instruction_args()
          }
+         Ts::ExpectTagOpenEnd => {
+            self.tag_attr_tokenize()
+         }
+         Ts::ExpectTagCloseEnd => {
+            self.tag_tokenize()
+         }
+         Ts::ExpectInterpolationClose
+         | Ts::ExpectRawInterpolationClose => {
+            self.interpolation_tokenize()
+         }
+         Ts::ExpectCommentClose => {
+            self.comment_tokenize()
+         }
+         Ts::ExpectVerbatimEnd => {
+            self.verbatim_tokenize()
+         }
+         Ts::ExpectRawTextEnd => {
+            self.raw_text_tokenize()
+         }
+         Ts::ExpectHtmlCommentClose
+         | Ts::ExpectCdataClose => {
+            self.markup_section_tokenize()
+         }
          Ts::Failed => {
             None
          }
@@ -89,6 +175,35 @@ impl Iterator for Tokenizer {
          }
       }
    }
-}
 
 
+
+   // Fail Tokenizer, when Error or Warning Token over the limit is tokenized.
+   // That Token is not returned and neither are buffered Tokens, since source
+   // is not tokenized further. The number of suppressed Error and Warning
+   // Tokens is returned within ErrorLimit.
+   fn error_limit_fail(&mut self, code: u16) -> Token {
+      let mut cnt_suppressed = 1;
+
+      if self.utf8_error.take().is_some() {
+         cnt_suppressed += 1;
+      }
+
+      if self.newline_warning.take().is_some() {
+         cnt_suppressed += 1;
+      }
+
+      while let Some(token) = self.tokenbuf.pop_front() {
+         if let Token::Error(..) | Token::Warning(..) = token {
+            cnt_suppressed += 1;
+         }
+      }
+
+      self.fail_token(Token::Fatal(ParseError::ErrorLimit(Source {
+         pos_zero: self.pos_zero,
+         component: Component::Tokenizer,
+         line: line!(),
+         code: code,
+      }, cnt_suppressed)))
+   }
+}
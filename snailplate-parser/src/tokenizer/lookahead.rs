@@ -0,0 +1,199 @@
+// Lookahead over Tokenizer's Token stream. Consumers, i.e. IncludeResolver or
+// parser, often have to look at a few Tokens ahead before they know what to do
+// with the current one. Instead of each of them buffering Tokens on its own,
+// Tokenizer keeps Tokens it has returned after checkpoint and Tokens that were
+// peeked, and returns them again from Iterator.
+//
+// Kept Tokens have passed return_tokenized when they were tokenized, thus
+// Tokenizer positions are those of the last tokenized Token, not the last
+// returned one. Rewind only moves within kept Tokens, source is never
+// tokenized twice.
+
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   parse_error::{
+      ParseError,
+      Component,
+      Source,
+   },
+};
+
+
+
+/// Position in Tokenizer's Token stream, that Iterator can be rewound to, see
+/// Tokenizer::checkpoint.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Checkpoint {
+   // Tokenizer generation, see Tokenizer::reset.
+   generation: usize,
+
+   // Unique id, rewind refuses checkpoint, that is not among live ones.
+   id: usize,
+
+   // Number of Tokens returned by Iterator before checkpoint was made.
+   cnt_token: usize,
+}
+
+
+
+impl Tokenizer<'_> {
+   /// Make checkpoint at current position in Token stream. Tokens returned by
+   /// Iterator after this are kept, thus Iterator can return them again after
+   /// rewind. Checkpoints nest, Tokens are kept till every checkpoint is
+   /// released with checkpoint_release.
+   ///
+   /// # Return
+   ///
+   /// * 'Err(Token)' - NoMemory, if checkpoint could not be kept.
+   pub fn checkpoint(&mut self) -> Result<Checkpoint, Token> {
+      // We do not want to panic if there is not enough memory.
+      let cl = &mut self.checkpoint_live;
+      let cap = cl.capacity();
+      let len = cl.len();
+      if cap < len + 1 {
+         if let Err(..) = cl.try_reserve(8) {
+            return Err(self.fail_token(
+               Token::Fatal(ParseError::NoMemory(Source {
+                  pos_zero: self.pos_zero,
+                  component: Component::Tokenizer,
+                  line: line!(),
+                  code: 0,
+               }))
+            ));
+         }
+      }
+
+      let id = self.checkpoint_id_next;
+      self.checkpoint_id_next += 1;
+      self.checkpoint_live.push(id);
+
+      Ok(Checkpoint {
+         generation: self.generation,
+         id: id,
+         cnt_token: self.lookahead_base + self.lookahead_pos,
+      })
+   }
+
+
+
+   /// Release the last checkpoint. When there are no more checkpoints, Tokens
+   /// already returned by Iterator are dropped, peeked Tokens are kept till
+   /// they are returned.
+   pub fn checkpoint_release(&mut self) {
+      self.checkpoint_live.pop();
+
+      if self.checkpoint_live.is_empty() {
+         self.lookahead.drain(..self.lookahead_pos);
+         self.lookahead_base += self.lookahead_pos;
+         self.lookahead_pos = 0;
+      }
+   }
+
+
+
+   /// Rewind Iterator to given checkpoint, thus Tokens returned after it are
+   /// returned again. Checkpoint is not released, it is allowed to rewind to
+   /// it more than once.
+   ///
+   /// # Return
+   ///
+   /// * 'Err(Token)' - LookaheadError, if checkpoint is already released or
+   ///   it was made before reset. Iterator position is not changed then.
+   pub fn rewind(&mut self, checkpoint: Checkpoint) -> Result<(), Token> {
+      let cnt_token = checkpoint.cnt_token;
+      let cnt_token_max = self.lookahead_base + self.lookahead.len();
+
+      if !self.checkpoint_live.contains(&checkpoint.id)
+      || checkpoint.generation != self.generation
+      || cnt_token < self.lookahead_base
+      || cnt_token > cnt_token_max
+      {
+         return Err(Token::Error(ParseError::LookaheadError(Source {
+            pos_zero: self.pos_zero,
+            component: Component::Tokenizer,
+            line: line!(),
+            code: 24,
+         })));
+      }
+
+      self.lookahead_pos = cnt_token - self.lookahead_base;
+
+      Ok(())
+   }
+
+
+
+   /// Returns n-th Token, that Iterator would return next, without consuming
+   /// it; peek(0) is the next one. Tokens are tokenized as far as needed and
+   /// kept till Iterator returns them, source must not be pushed before that.
+   ///
+   /// # Return
+   ///
+   /// * 'Ok(None)' - Token stream ends before n-th Token, or streamed region
+   ///   needs more input.
+   ///
+   /// * 'Err(Token)' - NoMemory, if Token could not be kept.
+   pub fn peek(&mut self, n: usize) -> Result<Option<&Token>, Token> {
+      let idx = self.lookahead_pos + n;
+
+      while self.lookahead.len() <= idx {
+         let token = match self.token_next_limited() {
+            Some(token) => token,
+            None => return Ok(None),
+         };
+
+         if let Err(etoken) = self.lookahead_push(token) {
+            return Err(etoken);
+         }
+      }
+
+      Ok(self.lookahead.get(idx))
+   }
+
+
+
+   // Take the next kept Token for Iterator. If there is no checkpoint and it
+   // was the last kept Token, lookahead is cleared, capacity is kept.
+   #[inline(always)]
+   pub(super) fn lookahead_consume(&mut self) -> Token {
+      let token = self.lookahead[self.lookahead_pos].clone();
+      self.lookahead_pos += 1;
+
+      if self.checkpoint_live.is_empty()
+      && self.lookahead_pos == self.lookahead.len()
+      {
+         self.lookahead_base += self.lookahead_pos;
+         self.lookahead_pos = 0;
+         self.lookahead.clear();
+      }
+
+      token
+   }
+
+
+
+   // Keep Token in lookahead. We do not want to panic if there is not enough
+   // memory.
+   pub(super) fn lookahead_push(&mut self, token: Token) -> Result<(), Token> {
+      let la = &mut self.lookahead;
+      let cap = la.capacity();
+      let len = la.len();
+      if cap < len + 1 {
+         if let Err(..) = la.try_reserve(16) {
+            return Err(self.fail_token(
+               Token::Fatal(ParseError::NoMemory(Source {
+                  pos_zero: self.pos_zero,
+                  component: Component::Tokenizer,
+                  line: line!(),
+                  code: 0,
+               }))
+            ));
+         }
+      }
+
+      la.push(token);
+
+      Ok(())
+   }
+}
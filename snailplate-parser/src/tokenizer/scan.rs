@@ -0,0 +1,146 @@
+// Scanning for special bytes. Most of template bytes are plain text, that has
+// no meaning for Tokenizer, thus instead of matching each byte, hot loops jump
+// from one special byte to the next one with ByteSet.find.
+//
+// On x86_64 bytes are compared 16 at a time with SSE2, which is always
+// available there. Bytes that do not fill the whole vector, and all bytes on
+// other targets, are looked up in a table one by one. Feature
+// "scalar_tokenizer_scan" turns off SSE2 scanning, so that both ways can be
+// compared, see bench/tokenizer_bench.rs.
+
+
+
+// ByteSet is built once, when Syntax is built, thus the number of bytes is
+// known and small.
+const BYTESET_LEN_MAX: usize = 8;
+
+// Number of bytes, that are compared at once with SSE2.
+#[cfg(all(target_arch = "x86_64", not(feature = "scalar_tokenizer_scan")))]
+const CHUNK_LEN: usize = 16;
+
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct ByteSet {
+   // Lookup table for scalar scanning and ByteSet.contains.
+   table: [bool; 256],
+
+   // Bytes in set for vectorized scanning. Unused slots repeat the first
+   // byte, so that all slots can be compared without checking length.
+   bytes: [u8; BYTESET_LEN_MAX],
+}
+
+
+
+impl ByteSet {
+   // Build set from given bytes. Duplicates are allowed, they are kept only
+   // once. Caller must give at least one and at most BYTESET_LEN_MAX unique
+   // bytes, Syntax ensures that.
+   pub(super) fn new(bytes: &[u8]) -> Self {
+      let mut set = Self {
+         table: [false; 256],
+         bytes: [bytes[0]; BYTESET_LEN_MAX],
+      };
+
+      let mut len = 0;
+      for &byte in bytes {
+         if set.table[byte as usize] {
+            continue;
+         }
+
+         set.table[byte as usize] = true;
+         set.bytes[len] = byte;
+         len += 1;
+      }
+
+      set
+   }
+
+
+
+   // Returns true if given byte is in set.
+   #[inline(always)]
+   pub(super) fn contains(&self, byte: u8) -> bool {
+      self.table[byte as usize]
+   }
+
+
+
+   // Returns position of the first byte in set at or after pos. If there is
+   // no such byte, src.len() is returned.
+   #[inline(always)]
+   pub(super) fn find(&self, src: &[u8], pos: usize) -> usize {
+      #[cfg(all(target_arch = "x86_64", not(feature = "scalar_tokenizer_scan")))] {
+         // Special bytes are often close to each other, i.e. in tags and
+         // instruction arguments. Preparing vectors would cost more than
+         // looking up a few bytes, thus the first chunk is scanned in place.
+         let pos_scalar_max = src.len().min(pos + CHUNK_LEN);
+         let pos = self.find_scalar(src, pos, pos_scalar_max);
+         if pos < pos_scalar_max {
+            return pos;
+         }
+
+         self.find_sse2(src, pos)
+      }
+
+      #[cfg(not(all(target_arch = "x86_64", not(feature = "scalar_tokenizer_scan"))))] {
+         self.find_scalar(src, pos, src.len())
+      }
+   }
+
+
+
+   // Returns position of the first byte in set from pos till pos_max. If there
+   // is no such byte, pos_max is returned.
+   #[inline(always)]
+   fn find_scalar(&self, src: &[u8], pos: usize, pos_max: usize) -> usize {
+      let mut pos = pos;
+      while pos < pos_max && !self.table[src[pos] as usize] {
+         pos += 1;
+      }
+
+      pos
+   }
+
+
+
+   #[cfg(all(target_arch = "x86_64", not(feature = "scalar_tokenizer_scan")))]
+   #[inline(never)]
+   fn find_sse2(&self, src: &[u8], pos: usize) -> usize {
+      use std::arch::x86_64::{
+         __m128i,
+         _mm_set1_epi8,
+         _mm_loadu_si128,
+         _mm_cmpeq_epi8,
+         _mm_or_si128,
+         _mm_movemask_epi8,
+      };
+
+      let mut pos = pos;
+
+      // SAFETY: SSE2 is a part of x86_64 baseline, thus intrinsics are always
+      // available. Loads are unaligned and only whole chunks within src are
+      // loaded, see loop condition.
+      unsafe {
+         let needles = self.bytes.map(|byte| _mm_set1_epi8(byte as i8));
+
+         while pos + CHUNK_LEN <= src.len() {
+            let chunk = _mm_loadu_si128(src.as_ptr().add(pos) as *const __m128i);
+
+            let mut hit = _mm_cmpeq_epi8(chunk, needles[0]);
+            for needle in &needles[1..] {
+               hit = _mm_or_si128(hit, _mm_cmpeq_epi8(chunk, *needle));
+            }
+
+            let mask = _mm_movemask_epi8(hit);
+            if mask != 0 {
+               return pos + mask.trailing_zeros() as usize;
+            }
+
+            pos += CHUNK_LEN;
+         }
+      }
+
+      self.find_scalar(src, pos, src.len())
+   }
+}
@@ -0,0 +1,192 @@
+// Syntax stores delimiters, that Tokenizer uses to find template instructions,
+// interpolations and template comments. Delimiters are validated once, when
+// Syntax is built, thus Tokenizer does not have to check them while
+// tokenizing.
+use crate::{
+   token::Token,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::scan::ByteSet;
+
+
+
+// Delimiters are short, longer ones would only make templates harder to read.
+const DELIMITER_LEN_MAX: usize = 8;
+
+
+
+/// Delimiters for template instructions, interpolations and comments.
+///
+/// Default syntax is "@include(x)", "{{ x }}", "{!! x !!}" and
+/// "{{-- x --}}". Template comment delimiters are built from interpolation
+/// delimiters, i.e. for "[[ x ]]" comment is "[[-- x --]]". Escaped
+/// instruction sigil is the same sigil twice, i.e. "@@". Trim marker is "-"
+/// after sigil or open delimiter, or before close delimiter, i.e. "@-if(x)"
+/// or "[[- x -]]".
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Syntax {
+   // Byte that starts instruction, "@" by default.
+   pub(super) sigil: u8,
+
+   // Interpolation delimiters, "{{" and "}}" by default.
+   pub(super) open: Vec<u8>,
+   pub(super) close: Vec<u8>,
+
+   // Raw interpolation delimiters, "{!!" and "!!}" by default.
+   pub(super) raw_open: Vec<u8>,
+   pub(super) raw_close: Vec<u8>,
+
+   // Template comment delimiters, "{{--" and "--}}" by default.
+   pub(super) comment_open: Vec<u8>,
+   pub(super) comment_close: Vec<u8>,
+
+   // First bytes of open delimiters, they are kept here to avoid indexing
+   // into delimiters for every byte within tag.
+   pub(super) open_first: u8,
+   pub(super) raw_open_first: u8,
+
+   // Bytes that have special meaning in text: newlines, "<", ">", sigil and
+   // first bytes of open delimiters. Text is scanned for them, see ByteSet.
+   pub(super) text_special: ByteSet,
+
+   // Bytes that have special meaning within instruction arguments: newlines,
+   // quotes, parenthesis and sigil.
+   pub(super) args_special: ByteSet,
+}
+
+
+
+impl Syntax {
+   /// Returns default syntax: "@", "{{ }}", "{!! !!}" and "{{-- --}}".
+   pub fn new() -> Self {
+      Self {
+         sigil: 0x40 /* @ */,
+         open: b"{{".to_vec(),
+         close: b"}}".to_vec(),
+         raw_open: b"{!!".to_vec(),
+         raw_close: b"!!}".to_vec(),
+         comment_open: b"{{--".to_vec(),
+         comment_close: b"--}}".to_vec(),
+         open_first: 0x7B /* { */,
+         raw_open_first: 0x7B /* { */,
+         text_special: text_special_build(0x40 /* @ */, 0x7B /* { */, 0x7B /* { */),
+         args_special: args_special_build(0x40 /* @ */),
+      }
+   }
+
+
+
+   /// Build syntax with custom instruction sigil and interpolation delimiters,
+   /// i.e. `Syntax::custom(b'%', ("[[", "]]"), ("[!!", "!!]"))`.
+   ///
+   /// Sigil must be ASCII punctuation. Delimiters must be 1 to 8 ASCII
+   /// punctuation bytes. Neither of them can contain "<", ">", quotes or
+   /// parenthesis, since those are special in HTML or instruction arguments,
+   /// nor "-", which is trim marker, and delimiters can not contain sigil.
+   /// Interpolation and raw interpolation open delimiters can not be a prefix
+   /// of one another, otherwise it is not known which one is matched.
+   ///
+   /// Returns Token::Error with ParseError::InvalidDelimiter, if any of the
+   /// rules is broken.
+   pub fn custom(sigil: u8, interpolation: (&str, &str),
+      raw_interpolation: (&str, &str)
+   )
+      -> Result<Self, Token>
+   {
+      if !syntax_byte_valid(sigil) {
+         return Err(syntax_error(19));
+      }
+
+      let delimiters = [
+         interpolation.0, interpolation.1,
+         raw_interpolation.0, raw_interpolation.1,
+      ];
+
+      for delimiter in delimiters {
+         let bytes = delimiter.as_bytes();
+
+         if bytes.is_empty()
+         || bytes.len() > DELIMITER_LEN_MAX
+         || bytes.contains(&sigil)
+         || !bytes.iter().all(|&byte| syntax_byte_valid(byte))
+         {
+            return Err(syntax_error(20));
+         }
+      }
+
+      let open = interpolation.0.as_bytes();
+      let raw_open = raw_interpolation.0.as_bytes();
+      if open.starts_with(raw_open) || raw_open.starts_with(open) {
+         return Err(syntax_error(21));
+      }
+
+      let mut comment_open = open.to_vec();
+      comment_open.extend_from_slice(b"--");
+
+      let mut comment_close = b"--".to_vec();
+      comment_close.extend_from_slice(interpolation.1.as_bytes());
+
+      Ok(Self {
+         sigil: sigil,
+         open: open.to_vec(),
+         close: interpolation.1.as_bytes().to_vec(),
+         raw_open: raw_open.to_vec(),
+         raw_close: raw_interpolation.1.as_bytes().to_vec(),
+         comment_open: comment_open,
+         comment_close: comment_close,
+         open_first: open[0],
+         raw_open_first: raw_open[0],
+         text_special: text_special_build(sigil, open[0], raw_open[0]),
+         args_special: args_special_build(sigil),
+      })
+   }
+}
+
+
+
+// Returns true if byte can be used in sigil or delimiter.
+fn syntax_byte_valid(byte: u8) -> bool {
+   byte.is_ascii_punctuation() && !matches!(byte,
+      0x3C /* < */ | 0x3E /* > */
+      | 0x22 /* " */ | 0x27 /* ' */
+      | 0x28 /* ( */ | 0x29 /* ) */
+      | 0x2D /* - */
+   )
+}
+
+
+
+// Build set of bytes, that have special meaning in text.
+fn text_special_build(sigil: u8, open_first: u8, raw_open_first: u8) -> ByteSet {
+   ByteSet::new(&[
+      0x0A /* newline */, 0x0D /* \r */, 0x3C /* < */, 0x3E /* > */,
+      sigil, open_first, raw_open_first,
+   ])
+}
+
+
+
+// Build set of bytes, that have special meaning within instruction arguments.
+fn args_special_build(sigil: u8) -> ByteSet {
+   ByteSet::new(&[
+      0x0A /* newline */, 0x0D /* \r */, 0x22 /* " */, 0x27 /* ' */,
+      0x28 /* ( */, 0x29 /* ) */, sigil,
+   ])
+}
+
+
+
+// Build error Token, that is returned for invalid sigil or delimiters.
+fn syntax_error(code: u16) -> Token {
+   Token::Error(ParseError::InvalidDelimiter(Source {
+      pos_zero: 0,
+      component: Component::Tokenizer,
+      line: line!(),
+      code: code,
+   }))
+}
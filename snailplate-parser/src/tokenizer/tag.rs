@@ -0,0 +1,184 @@
+// In HTML sense, tags are words that follow < symbol. For example: div, span,
+// my-component, etc. Tokenizer only matches the shape of tag, it does not know
+// anything about HTML element names, thus "<foo" is as good as "<div".
+// Markup declarations that start with "<!" are matched as well, since they
+// would otherwise look like broken tags.
+//
+// Matched tag is a tuple in shape: (pos_start, pos_end), where both positions
+// are inclusive and relative to current region being tokenized. pos_start
+// always points to "<" byte.
+//
+use crate::tokenbody::TextContext;
+
+
+
+#[derive(Debug)]
+pub(super) enum Tag {
+   // This is matched for "<div", "<my-component", "<x:y", but not for "< div",
+   // "<1", "<!--", since those can not start a tag.
+   OpenStart(usize, usize),
+
+   // This is matched for "</div" when it is not immediately followed by ">",
+   // for example "</div  >" or "</div\n>".
+   CloseStart(usize, usize),
+
+   // This is matched for complete closing tags "</div>" and "</>".
+   Close(usize, usize),
+
+   // This is matched for HTML comment start "<!--".
+   HtmlCommentOpen(usize, usize),
+
+   // This is matched for "<!DOCTYPE" in any character case. The rest of
+   // declaration is tokenized as tag contents.
+   Doctype(usize, usize),
+
+   // This is matched for CDATA section start "<![CDATA[".
+   CdataOpen(usize, usize),
+
+   // Slice is not matched as tag.
+   None
+}
+
+
+
+// Returns true if given byte is allowed as the first byte of a tag name.
+#[inline(always)]
+fn tag_name_first(byte: u8) -> bool {
+   matches!(byte, 0x41..=0x5A /* A-Z */ | 0x61..=0x7A /* a-z */)
+}
+
+
+
+// Returns true if given byte is allowed within tag name after the first byte.
+// Dash is necessary for custom elements, colon for namespaced elements (svg,
+// xml). We do not try to be more strict than that, since it is not
+// Tokenizer's job to validate element names.
+#[inline(always)]
+fn tag_name_rest(byte: u8) -> bool {
+   matches!(byte,
+      0x41..=0x5A /* A-Z */
+      | 0x61..=0x7A /* a-z */
+      | 0x30..=0x39 /* 0-9 */
+      | 0x2D /* - */
+      | 0x5F /* _ */
+      | 0x3A /* : */
+      | 0x2E /* . */
+   )
+}
+
+
+
+// Returns TextContext for raw-text element with given name, i.e. "script", or
+// TextContext::Text if it is not a raw-text element. Content of raw-text
+// elements is not tokenized as HTML, so that "<" within JavaScript does not
+// start a tag. Name is matched ignoring character case.
+//
+// start - position (inclusive) in buffer, where tag name starts
+// end - position (inclusive) in buffer, where tag name ends
+#[inline(always)]
+pub(super) fn tag_raw_text(src: &[u8], start: usize, end: usize) -> TextContext {
+   if end < start || end >= src.len() {
+      return TextContext::Text;
+   }
+
+   let name = &src[start..end + 1];
+
+   if name.eq_ignore_ascii_case(b"script") {
+      TextContext::Script
+   }
+   else if name.eq_ignore_ascii_case(b"style") {
+      TextContext::Style
+   }
+   else if name.eq_ignore_ascii_case(b"textarea") {
+      TextContext::Textarea
+   }
+   else if name.eq_ignore_ascii_case(b"title") {
+      TextContext::Title
+   }
+   else {
+      TextContext::Text
+   }
+}
+
+
+
+// Function that tries to match markup declaration that starts with "<!" at
+// given position, i.e. "<!--", "<!DOCTYPE" or "<![CDATA[".
+#[inline(always)]
+fn tag_match_markup(src: &[u8], pos: usize) -> Tag {
+   let rest = &src[pos..];
+
+   if rest.starts_with(b"<!--") {
+      return Tag::HtmlCommentOpen(pos, pos + 3);
+   }
+
+   if rest.len() >= 9 && rest[..9].eq_ignore_ascii_case(b"<!doctype") {
+      return Tag::Doctype(pos, pos + 8);
+   }
+
+   if rest.starts_with(b"<![CDATA[") {
+      return Tag::CdataOpen(pos, pos + 8);
+   }
+
+   Tag::None
+}
+
+
+
+// Function that tries to match tag start at given position. If it returns
+// None, then this means that "<" at given position can not start a tag and
+// should be treated as text.
+//
+// pos - position in buffer where "<" byte is.
+#[inline(always)]
+pub(super) fn tag_match(src: &[u8], pos: usize) -> Tag {
+   let pos_max = src.len();
+
+   // Caller should ensure that tag_match is called with correct parameters,
+   // but we do not want to panic if it is not.
+   if pos >= pos_max || src[pos] != 0x3C /* < */ {
+      return Tag::None;
+   }
+
+   let mut pos_name = pos + 1;
+   let closing = if pos_name < pos_max && src[pos_name] == 0x2F /* / */ {
+      pos_name += 1;
+      true
+   }
+   else {
+      false
+   };
+
+   if pos_name >= pos_max {
+      return Tag::None;
+   }
+
+   if !closing && src[pos_name] == 0x21 /* ! */ {
+      return tag_match_markup(src, pos);
+   }
+
+   // This is "</>", which closes last open tag.
+   if closing && src[pos_name] == 0x3E /* > */ {
+      return Tag::Close(pos, pos_name);
+   }
+
+   if !tag_name_first(src[pos_name]) {
+      return Tag::None;
+   }
+
+   let mut pos_end = pos_name;
+   while pos_end + 1 < pos_max && tag_name_rest(src[pos_end + 1]) {
+      pos_end += 1;
+   }
+
+   if !closing {
+      return Tag::OpenStart(pos, pos_end);
+   }
+
+   if pos_end + 1 < pos_max && src[pos_end + 1] == 0x3E /* > */ {
+      Tag::Close(pos, pos_end + 1)
+   }
+   else {
+      Tag::CloseStart(pos, pos_end)
+   }
+}
@@ -14,7 +14,6 @@ use crate::{
 use super::tokenlist_match_or_fail;
 
 // cargo test tokenizer::test::tokenizer_test_buf_generic -- --nocapture
-// cargo test -F tokenbuf_push_guard tokenizer::test::tokenizer_test_buf_generic -- --nocapture
 // This tests generic functionality for tokenbuf. Do inserts happen at all,
 // can items be retrieved in expected order, aren't no extra items returned
 // when they should not.
@@ -39,11 +38,11 @@ fn tokenizer_test_buf_generic() {
       }
    }
 
-   assert_eq!(t.tokenbuf.num_tokens(), t.tokenbuf.buf_len(), "Bad num_tokens count!");
+   assert_eq!(t.tokenbuf.num_tokens(), 3, "Bad num_tokens count!");
 
    for i in 0..3 {
       match t.tokenbuf_consume() {
-         Ok(Some(token)) => match token {
+         Some(token) => match token {
             // receiving some token is fine. Now check if it has expected index.
             Token::Real(body) => match body {
                // Here actually it would be less code if we extracted span by
@@ -64,100 +63,87 @@ fn tokenizer_test_buf_generic() {
                panic!("Received unexpected token: {:?}", tok);
             }
          }
-         Ok(None) => {
+         None => {
             panic!("Received none, although expected token.");
          }
-         Err(etok) => {
-            panic!("Could not get token out: Error: {:?}", etok);
-         }
       }
    }
 
    // All tokens should have been consumed.
-   match t.tokenbuf_consume() {
-      Ok(Some(tok)) => {
-         panic!("Received some token when none should be available: {:?}", tok);
-      }
-      Ok(None) => {
-         /* This is how it should be. */
-      }
-      Err(etok) => {
-         panic!("Could not get token out: Error: {:?}", etok);
-      }
+   if let Some(tok) = t.tokenbuf_consume() {
+      panic!("Received some token when none should be available: {:?}", tok);
    }
 }
 
 
 
 // cargo test tokenizer::test::tokenizer_test_buf_mixed_in_out -- --nocapture
-// cargo test -F tokenbuf_push_guard tokenizer::test::tokenizer_test_buf_mixed_in_out -- --nocapture
-// This test tests if tokenbuf allows mixed in and out operations. In general
-// i don't think there will be problems other than increased memory consumption
-// if tokenbuf does not work as intended in this regard, but at this point in
-// time i want to be sure that in/out policy is enforced.
-// This test should be run only if tokenbuf push guard is enabled. Otherwise
-// there is no detection code compiled in.
+// This tests if tokenbuf allows mixed in and out operations. Tokens pushed into
+// half-consumed tokenbuf must be returned after the ones that were already
+// there.
 #[test]
-#[cfg(feature = "tokenbuf_push_guard")]
 fn tokenizer_test_buf_mixed_in_out() {
    let mut t = Tokenizer::new();
 
-   let tok = Token::Real(TokenBody::Defered(Span{
-      index: 0,
-      pos_region: 0,
-      pos_line: 0,
-      pos_zero: 0,
-      line: 0,
-      length: 1
-   }));
-
-   if let Err(..) = t.tokenbuf_push(tok) {
-      panic!("could not push token into tokenbuf!");
-   }
-
-   let tok = Token::Real(TokenBody::Defered(Span{
-      index: 0,
-      pos_region: 1,
-      pos_line: 1,
-      pos_zero: 1,
-      line: 0,
-      length: 1
-   }));
+   for i in 0..2 {
+      let tok = Token::Real(TokenBody::Defered(Span{
+         index: 0,
+         pos_region: i,
+         pos_line: i,
+         pos_zero: i,
+         line: 0,
+         length: 1
+      }));
 
-   if let Err(..) = t.tokenbuf_push(tok) {
-      panic!("could not push second token into tokenbuf!");
+      if let Err(..) = t.tokenbuf_push(tok) {
+         panic!("could not push token into tokenbuf!");
+      }
    }
 
    match t.tokenbuf_consume() {
-      Ok(Some(..)) => { /* receiving some token is fine. */ }
-      Ok(None) => {
-         panic!("Received none, although expected one token.");
+      Some(token) => {
+         assert_eq!(token.span_clone().map(|s| s.pos_zero), Some(0),
+            "Wrong order for consumed token."
+         );
       }
-      Err(etok) => {
-         panic!("Could not get token out: Error: {:?}", etok);
+      None => {
+         panic!("Received none, although expected one token.");
       }
    }
 
-   // Now try to push in half-consumed tokenbuf. This should fail by desing.
+   // Now push in half-consumed tokenbuf.
    let tok = Token::Real(TokenBody::Defered(Span{
       index: 0,
-      pos_region: 1,
-      pos_line: 1,
-      pos_zero: 1,
+      pos_region: 2,
+      pos_line: 2,
+      pos_zero: 2,
       line: 0,
       length: 1
    }));
 
-   if let Ok(_) = t.tokenbuf_push(tok) {
-      panic!("Tokenbuf allowed to do push when not being fully consumed before!");
+   if let Err(..) = t.tokenbuf_push(tok) {
+      panic!("could not push token into half-consumed tokenbuf!");
+   }
+
+   for i in 1..3 {
+      match t.tokenbuf_consume() {
+         Some(token) => {
+            assert_eq!(token.span_clone().map(|s| s.pos_zero), Some(i),
+               "Wrong order for consumed token."
+            );
+         }
+         None => {
+            panic!("Received none, although expected token.");
+         }
+      }
    }
 
-   // If error was returned before, test has passed.
+   assert_eq!(t.tokenbuf.num_tokens(), 0, "Bad num_tokens count!");
 }
 
 
 
-// cargo test -F tokenbuf_push_guard tokenizer::test::tokenizer_src_push -- --nocapture
+// cargo test tokenizer::test::tokenizer_src_push -- --nocapture
 #[test]
 fn tokenizer_src_push() {
    println!("Tokenizer src_push test");
@@ -457,7 +443,7 @@ fn test_whitespace_into_tokenbuf(
          // we must test already inserted tokens, otherwise internal state
          // will not be as expected for upcoming testing items/tuples.
 
-         let num_tokens = t.tokenbuf.buf_len();
+         let num_tokens = t.tokenbuf.num_tokens();
          if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t,
             &expect[slice_base..slice_base + num_tokens], false
          ){
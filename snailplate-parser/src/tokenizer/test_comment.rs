@@ -0,0 +1,129 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::TokenBody,
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests that instruction and interpolation within comment are not
+// tokenized.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_comment::tokenizer_comment_test_01 -- --nocapture
+#[test]
+fn tokenizer_comment_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a{{-- @include(x) {{ y }} --}}b".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 29
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 30, pos_region: 30, pos_zero: 30, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests comment that spans over two lines within a tag. After comment is
+// closed, Tokenizer must continue to tokenize tag.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_comment::tokenizer_comment_test_02 -- --nocapture
+#[test]
+fn tokenizer_comment_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<a {{-- x\n>y --}}>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 6
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 10, pos_zero: 10, length: 7
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 1, pos_line: 7, pos_region: 17, pos_zero: 17, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests comment that is never closed. Error must point to comment start.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_comment::tokenizer_comment_test_03 -- --nocapture
+#[test]
+fn tokenizer_comment_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "x\n{{-- a\nb".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 1
+      })),
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 2, pos_zero: 2, length: 6
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 6, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 2, pos_line: 0, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Error(ParseError::OpenComment(Source {
+         pos_zero: 2,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 10,
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -0,0 +1,160 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::TokenBody,
+   span::Span,
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests escaped @ symbol in Defered text, which must not start @include
+// instruction.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_escape::tokenizer_escape_test_01 -- --nocapture
+#[test]
+fn tokenizer_escape_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "user@@include.com".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 4
+      })),
+      Token::Real(TokenBody::EscapedAt(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 11
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests that "@@@include(x)" is escaped @ symbol followed by
+// instruction.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_escape::tokenizer_escape_test_02 -- --nocapture
+#[test]
+fn tokenizer_escape_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@@@include(x)".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::EscapedAt(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 8
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests escaped @ symbol within instruction arguments, including the
+// case, where it is the last thing before closing parenthesis.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_escape::tokenizer_escape_test_03 -- --nocapture
+#[test]
+fn tokenizer_escape_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@include(a@@b@@)".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::EscapedAt(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 1
+      })),
+      Token::Real(TokenBody::EscapedAt(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 2
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests instruction arguments where closing parenthesis is the first
+// byte on a new line. Buffered Newline must be returned before CloseParen.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_escape::tokenizer_escape_test_04 -- --nocapture
+#[test]
+fn tokenizer_escape_test_04() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@include(x\n)".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 11, pos_zero: 11, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -20,3 +20,52 @@ fn tokenizer_ident_test_01() {
 }
 
 
+
+// cargo test -F dbg_tokenizer_verbose tokenizer::test_ident::tokenizer_ident_test_02 -- --nocapture
+#[test]
+fn tokenizer_ident_test_02() {
+   let buf = "elseif(x)".as_bytes();
+
+   let ident = ident_match(&buf, 0, 5);
+   if let Ident::ElseIf(start, end) = ident {
+      assert_eq!(start, 0);
+      assert_eq!(end, 5);
+   }
+   else {
+      panic!("Bad ident returned. Expected Ident::ElseIf, got: {:?}", ident);
+   }
+
+   let ident = ident_match(&buf, 0, 3);
+   if let Ident::Else(..) = ident { }
+   else {
+      panic!("Bad ident returned. Expected Ident::Else, got: {:?}", ident);
+   }
+
+   let ident = ident_match(&buf, 0, 4);
+   if let Ident::None = ident { }
+   else {
+      panic!("Bad ident returned. Expected Ident::None, got: {:?}", ident);
+   }
+}
+
+
+
+// cargo test -F dbg_tokenizer_verbose tokenizer::test_ident::tokenizer_ident_test_03 -- --nocapture
+#[test]
+fn tokenizer_ident_test_03() {
+   let cases: [(&str, Option<&str>); 8] = [
+      ("Include", Some("include")),
+      ("inclde", Some("include")),
+      ("inlcude", Some("include")),
+      ("includee", Some("include")),
+      ("ENDIF", Some("endif")),
+      ("iff", None),
+      ("foo", None),
+      ("example", None),
+   ];
+
+   for (word, expect) in cases {
+      let buf = word.as_bytes();
+      assert_eq!(ident_suggest(buf, 0, buf.len() - 1), expect, "word: {}", word);
+   }
+}
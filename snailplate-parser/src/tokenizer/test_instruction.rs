@@ -5,6 +5,7 @@ use crate::{
    span::Span,
    parse_error::{
       ParseError,
+      Suggestion,
       Source,
       Component,
    },
@@ -147,7 +148,8 @@ fn tokenizer_instruction_include_test_03() {
 
 
 // This tests unfinished include instruction handling. This test should hit
-// instruction_tokenize_unfinished case when tokenizing instruction.
+// instruction_tokenize_unfinished case when tokenizing instruction. "inclu" is
+// too far from any instruction, thus there is no warning.
 //
 // cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_04 -- --nocapture
 #[test]
@@ -160,11 +162,12 @@ fn tokenizer_instruction_include_test_04() {
 
 
    let list: Vec<Token> = [
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
       Token::Real(TokenBody::Defered(Span {
-         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 6
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 5
       })),
-      // TODO: in future we should test Phantom tokens as well, that have 
-      // warning information with suggestions.
    ].to_vec();
 
    if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
@@ -176,6 +179,8 @@ fn tokenizer_instruction_include_test_04() {
 
 
 
+// This tests whitespace between @ symbol and instruction name. It is not an
+// instruction, but user is warned with a suggestion.
 // cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_05 -- --nocapture
 #[test]
 fn tokenizer_instruction_include_test_05() {
@@ -189,11 +194,15 @@ fn tokenizer_instruction_include_test_05() {
       Token::Real(TokenBody::UnescapedAt(Span {
          index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
       })),
+      Token::Warning(ParseError::InstructionNearMiss(Source {
+         pos_zero: 0,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 8,
+      }, Suggestion::Instruction("include"))),
       Token::Real(TokenBody::Defered(Span {
          index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 9
       })),
-      // TODO: in future we should test Phantom tokens as well, that have
-      // warning information with suggestions.
    ].to_vec();
 
    if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
@@ -431,22 +440,32 @@ fn tokenizer_instruction_include_test_11() {
 
 
 
-// cargo test -F future_passing_tests -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_102 -- --nocapture
+// This tests that parenthesis within string literal are not counted.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_12 -- --nocapture
 #[test]
-#[cfg(feature = "future_passing_tests")]
-fn tokenizer_instruction_include_test_102() {
-   println!("Starging iterator test 05");
+fn tokenizer_instruction_include_test_12() {
    let mut t = Tokenizer::new();
 
    #[allow(unused_must_use)] {
-      t.src_push(None, "@if(prop == \")xx\")".into());
+      t.src_push(None, "@include(\")(\" x)".into());
    }
 
    let list: Vec<Token> = [
       Token::Real(TokenBody::Include(Span {
          index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
       })),
-      // TODO:
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::StringLiteral(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 4
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 2
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 1
+      })),
    ].to_vec();
 
    if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
@@ -458,24 +477,133 @@ fn tokenizer_instruction_include_test_102() {
 
 
 
-// cargo test -F future_passing_tests -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_103 -- --nocapture
+// This tests string literal with escaped quote, that is not closed before
+// newline. Only the rest of the line is taken as string literal, instruction
+// is closed on the next line.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_13 -- --nocapture
 #[test]
-#[cfg(feature = "future_passing_tests")]
-fn tokenizer_instruction_include_test_103() {
-   println!("Starging iterator test 05");
+fn tokenizer_instruction_include_test_13() {
    let mut t = Tokenizer::new();
 
-   // In this case Tokenizer should pass for the wrong reasons though, because
-   // instruction contains matching parenthesis.
    #[allow(unused_must_use)] {
-      t.src_push(None, "@if(/*(*/ prop == \")xx\")".into());
+      t.src_push(None, "@include('a\\'b)\nX)".into());
    }
 
    let list: Vec<Token> = [
       Token::Real(TokenBody::Include(Span {
          index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
       })),
-      // TODO:
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::StringLiteral(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 6
+      })),
+      Token::Error(ParseError::OpenString(Source {
+         pos_zero: 9,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 7
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 16, pos_zero: 16, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 1, pos_line: 1, pos_region: 17, pos_zero: 17, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests instructions without arguments, and that @else is not matched
+// as @elseif or vice versa.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_14 -- --nocapture
+#[test]
+fn tokenizer_instruction_include_test_14() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@if(x) @else B @endif".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::If(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 1
+      })),
+      Token::Real(TokenBody::Else(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 5
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 3
+      })),
+      Token::Real(TokenBody::EndIf(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 6
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests instructions with optional arguments.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_15 -- --nocapture
+#[test]
+fn tokenizer_instruction_include_test_15() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@empty @empty(x)@break".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Empty(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 6
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 1
+      })),
+      Token::Real(TokenBody::Empty(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 6
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 14, pos_region: 14, pos_zero: 14, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 1
+      })),
+      Token::Real(TokenBody::Break(Span {
+         index: 0, line: 0, pos_line: 16, pos_region: 16, pos_zero: 16, length: 6
+      })),
    ].to_vec();
 
    if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
@@ -486,3 +614,254 @@ fn tokenizer_instruction_include_test_103() {
 }
 
 
+
+// This tests unknown instruction with arguments. Tokenizer must not stop, @
+// symbol is returned as UnescapedAt with a warning that suggests escaping.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_16 -- --nocapture
+#[test]
+fn tokenizer_instruction_include_test_16() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a @foo(x)".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Warning(ParseError::InstructionNearMiss(Source {
+         pos_zero: 2,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 8,
+      }, Suggestion::EscapeAt)),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 6
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_102 -- --nocapture
+#[test]
+fn tokenizer_instruction_include_test_102() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@if(prop == \")xx\")".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::If(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 8
+      })),
+      Token::Real(TokenBody::StringLiteral(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 5
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 17, pos_region: 17, pos_zero: 17, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests parenthesis within expression comment. Tokenizer does not know
+// expression comments, thus "(" within "/* */" is counted and the last ")"
+// closes it, not the instruction. String literal is not counted.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_103 -- --nocapture
+#[test]
+fn tokenizer_instruction_include_test_103() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@if(/*(*/ prop == \")xx\")".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::If(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 14
+      })),
+      Token::Real(TokenBody::StringLiteral(Span {
+         index: 0, line: 0, pos_line: 18, pos_region: 18, pos_zero: 18, length: 5
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 23, pos_region: 23, pos_zero: 23, length: 1
+      })),
+      Token::Error(ParseError::OpenInstruction(Source {
+         pos_zero: 0,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 0,
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests instruction name with bad character case and with a typo. Both
+// are returned as UnescapedAt with a warning that suggests instruction name.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_17 -- --nocapture
+#[test]
+fn tokenizer_instruction_include_test_17() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@Include(a)@inclde(b)".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Warning(ParseError::InstructionNearMiss(Source {
+         pos_zero: 0,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 8,
+      }, Suggestion::Instruction("include"))),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 10
+      })),
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Warning(ParseError::InstructionNearMiss(Source {
+         pos_zero: 11,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 8,
+      }, Suggestion::Instruction("include"))),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 9
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests instruction without open parenthesis and @ symbol in e-mail
+// address. Tokenizer must not swallow the rest of the text, e-mail address
+// does not produce any warning.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_18 -- --nocapture
+#[test]
+fn tokenizer_instruction_include_test_18() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@include x a@b.c".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Warning(ParseError::InstructionNotOpen(Source {
+         pos_zero: 0,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 9,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 11
+      })),
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 3
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests instruction names without arguments in e-mail addresses. @
+// symbol after a letter or digit does not start an instruction, thus it is
+// returned as UnescapedAt without any warning.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_instruction::tokenizer_instruction_include_test_19 -- --nocapture
+#[test]
+fn tokenizer_instruction_include_test_19() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "info@empty.org me@else.lv (@else)".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 4
+      })),
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 12
+      })),
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 17, pos_region: 17, pos_zero: 17, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 18, pos_region: 18, pos_zero: 18, length: 9
+      })),
+      Token::Real(TokenBody::Else(Span {
+         index: 0, line: 0, pos_line: 27, pos_region: 27, pos_zero: 27, length: 5
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 32, pos_region: 32, pos_zero: 32, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
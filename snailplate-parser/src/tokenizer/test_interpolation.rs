@@ -0,0 +1,181 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::{TokenBody, TextContext},
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests escaped interpolation within Defered text.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_interpolation::tokenizer_interpolation_test_01 -- --nocapture
+#[test]
+fn tokenizer_interpolation_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "Hi {{ name }}!".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 2
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 6
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests raw interpolation, where expression spans over two lines. "}}"
+// must not close raw interpolation.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_interpolation::tokenizer_interpolation_test_02 -- --nocapture
+#[test]
+fn tokenizer_interpolation_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "{!! a}}\nb !!}".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::RawInterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 4
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 8, pos_zero: 8, length: 2
+      })),
+      Token::Real(TokenBody::RawInterpolationClose(Span {
+         index: 0, line: 1, pos_line: 2, pos_region: 10, pos_zero: 10, length: 3
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests interpolation within quoted attribute value. After interpolation
+// is closed, Tokenizer must continue to tokenize tag.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_interpolation::tokenizer_interpolation_test_03 -- --nocapture
+#[test]
+fn tokenizer_interpolation_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<a href=\"{{u}}>\">".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 4
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 2
+      }, TextContext::Tag)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 2
+      })),
+      Token::Real(TokenBody::AttrValue(Span {
+         index: 0, line: 0, pos_line: 14, pos_region: 14, pos_zero: 14, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 1
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 16, pos_region: 16, pos_zero: 16, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests interpolation that is never closed.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_interpolation::tokenizer_interpolation_test_04 -- --nocapture
+#[test]
+fn tokenizer_interpolation_test_04() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "X{{ name }".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 2
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 7
+      })),
+      Token::Error(ParseError::OpenInterpolation(Source {
+         pos_zero: 1,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 4,
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
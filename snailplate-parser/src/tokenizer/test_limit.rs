@@ -0,0 +1,94 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::TokenBody,
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests warning limit. The first warning is returned, the second one is
+// replaced by ErrorLimit and nothing is tokenized after it.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_limit::tokenizer_limit_test_01 -- --nocapture
+#[test]
+fn tokenizer_limit_test_01() {
+   let mut t = Tokenizer::new();
+   t.warning_limit_set(1);
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a < b > c\n<p".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::Lt(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Warning(ParseError::UnescapedLt(Source {
+         pos_zero: 2,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 5,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 3
+      })),
+      Token::Real(TokenBody::Gt(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 1
+      })),
+      Token::Fatal(ParseError::ErrorLimit(Source {
+         pos_zero: 7,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 23,
+      }, 1)),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests error limit 0. InvalidUtf8 error is replaced by ErrorLimit, and
+// UnescapedLt warning, that is already buffered, is suppressed as well.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_limit::tokenizer_limit_test_02 -- --nocapture
+#[test]
+fn tokenizer_limit_test_02() {
+   let mut t = Tokenizer::new();
+   t.error_limit_set(0);
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, b"\xFFa < b".to_vec());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      })),
+      Token::Fatal(ParseError::ErrorLimit(Source {
+         pos_zero: 3,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 22,
+      }, 2)),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -0,0 +1,282 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::{TokenBody, TextContext},
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests peek and rewind. Peeked Tokens are returned by Iterator in the
+// same order, Tokens returned after checkpoint are returned again after
+// rewind, thus the whole list is matched from the start.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_lookahead::tokenizer_lookahead_test_01 -- --nocapture
+#[test]
+fn tokenizer_lookahead_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<p>{{ x }}</p>".into());
+   }
+
+   let first = match t.peek(0) {
+      Ok(Some(token)) => token.clone(),
+      got => panic!("Token must be peeked, got: {:?}", got),
+   };
+
+   let fourth = match t.peek(3) {
+      Ok(Some(token)) => token.clone(),
+      got => panic!("Token must be peeked, got: {:?}", got),
+   };
+
+   let checkpoint = t.checkpoint().expect("Checkpoint must be made.");
+   assert_eq!(t.next(), Some(first));
+   t.next();
+   t.next();
+   assert_eq!(t.next(), Some(fourth));
+
+   assert_eq!(t.rewind(checkpoint), Ok(()));
+   t.checkpoint_release();
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 2
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 2
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 4
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests lookahead errors. Rewind to released checkpoint is refused and
+// source can not be pushed while peeked Tokens are not returned. Warning
+// returned again after rewind is not counted against the limit twice.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_lookahead::tokenizer_lookahead_test_02 -- --nocapture
+#[test]
+fn tokenizer_lookahead_test_02() {
+   let mut t = Tokenizer::new();
+   t.warning_limit_set(1);
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a < b".into());
+   }
+
+   let checkpoint = t.checkpoint().expect("Checkpoint must be made.");
+   while t.next().is_some() {}
+   assert_eq!(t.rewind(checkpoint), Ok(()));
+
+   t.checkpoint_release();
+   match t.rewind(checkpoint) {
+      Err(Token::Error(ParseError::LookaheadError(source))) => {
+         assert_eq!(source.code, 24);
+      }
+      got => panic!("Rewind must be refused, got: {:?}", got),
+   }
+
+   if let Ok(None) | Err(..) = t.peek(0) {
+      panic!("Token must be peeked");
+   }
+
+   match t.src_push(None, "c".into()) {
+      Err(Token::Error(ParseError::LookaheadError(source))) => {
+         assert_eq!(source.code, 25);
+      }
+      got => panic!("Source push must be refused, got: {:?}", got),
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::Lt(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Warning(ParseError::UnescapedLt(Source {
+         pos_zero: 2,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 5,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 2
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+
+   assert_eq!(t.src_push(None, "c".into()), Ok(None));
+}
+
+
+
+// This tests rewind to released checkpoints, while Tokens are returned
+// without checkpoint and while outer checkpoint is still held. Both are
+// refused and Iterator position is not changed by refused rewind.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_lookahead::tokenizer_lookahead_test_03 -- --nocapture
+#[test]
+fn tokenizer_lookahead_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a < b < c".into());
+   }
+
+   let released = t.checkpoint().expect("Checkpoint must be made.");
+   t.checkpoint_release();
+   t.next();
+   t.next();
+
+   let outer = t.checkpoint().expect("Checkpoint must be made.");
+   t.next();
+   let inner = t.checkpoint().expect("Checkpoint must be made.");
+   t.next();
+   t.checkpoint_release();
+
+   for checkpoint in [released, inner] {
+      match t.rewind(checkpoint) {
+         Err(Token::Error(ParseError::LookaheadError(source))) => {
+            assert_eq!(source.code, 24);
+         }
+         got => panic!("Rewind must be refused, got: {:?}", got),
+      }
+   }
+
+   assert_eq!(t.rewind(outer), Ok(()));
+   t.checkpoint_release();
+
+   let list: Vec<Token> = [
+      Token::Warning(ParseError::UnescapedLt(Source {
+         pos_zero: 2,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 5,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 3
+      })),
+      Token::Real(TokenBody::Lt(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 1
+      })),
+      Token::Warning(ParseError::UnescapedLt(Source {
+         pos_zero: 6,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 5,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 2
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests peek across @include, the way IncludeResolver does it. Source can
+// not be pushed while instruction Tokens are peeked; after they are returned,
+// pushed region is tokenized right after ")", before the rest of including
+// region.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_lookahead::tokenizer_lookahead_test_04 -- --nocapture
+#[test]
+fn tokenizer_lookahead_test_04() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@include(x.html)y".into());
+   }
+
+   // Peek till ")", StateChange Tokens are peeked as well.
+   let mut cnt_peeked = 0;
+   loop {
+      match t.peek(cnt_peeked) {
+         Ok(Some(Token::Real(TokenBody::CloseParen(..)))) => break,
+         Ok(Some(..)) => cnt_peeked += 1,
+         got => panic!("CloseParen must be peeked, got: {:?}", got),
+      }
+   }
+   cnt_peeked += 1;
+
+   match t.src_push(Some("x.html"), "z".into()) {
+      Err(Token::Error(ParseError::LookaheadError(source))) => {
+         assert_eq!(source.code, 25);
+      }
+      got => panic!("Source push must be refused, got: {:?}", got),
+   }
+
+   let mut batch: Vec<Token> = Vec::new();
+   for _ in 0..cnt_peeked {
+      match t.next() {
+         Some(Token::StateChange) => {}
+         Some(token) => batch.push(token),
+         None => panic!("Peeked Token must be returned"),
+      }
+   }
+
+   assert_eq!(batch, [
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 6
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 1
+      })),
+   ]);
+
+   assert_eq!(t.src_push(Some("x.html"), "z".into()), Ok(None));
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 1, line: 0, pos_line: 0, pos_region: 0, pos_zero: 16, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 16, pos_region: 16, pos_zero: 17, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -0,0 +1,146 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::{TokenBody, TextContext},
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests DOCTYPE and HTML comment. Tags within HTML comment are not
+// tokenized, but interpolation is.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_markup::tokenizer_markup_test_01 -- --nocapture
+#[test]
+fn tokenizer_markup_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<!doctype html><!-- <b>{{x}} -->c".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Doctype(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 9
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 4
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 14, pos_region: 14, pos_zero: 14, length: 1
+      })),
+      Token::Real(TokenBody::HtmlCommentOpen(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 4
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 19, pos_region: 19, pos_zero: 19, length: 4
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 23, pos_region: 23, pos_zero: 23, length: 2
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 25, pos_region: 25, pos_zero: 25, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 0, pos_line: 26, pos_region: 26, pos_zero: 26, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 28, pos_region: 28, pos_zero: 28, length: 1
+      })),
+      Token::Real(TokenBody::HtmlCommentClose(Span {
+         index: 0, line: 0, pos_line: 29, pos_region: 29, pos_zero: 29, length: 3
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 32, pos_region: 32, pos_zero: 32, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests CDATA section, "<" within it is not a tag start nor Lt.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_markup::tokenizer_markup_test_02 -- --nocapture
+#[test]
+fn tokenizer_markup_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<![CDATA[a<b]]>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::CdataOpen(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 9
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 3
+      })),
+      Token::Real(TokenBody::CdataClose(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 3
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests HTML comment that is never closed. Error must point to "<!--".
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_markup::tokenizer_markup_test_03 -- --nocapture
+#[test]
+fn tokenizer_markup_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "x<!-- a\nb".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::HtmlCommentOpen(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 4
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 2
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Error(ParseError::OpenHtmlComment(Source {
+         pos_zero: 1,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 12,
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
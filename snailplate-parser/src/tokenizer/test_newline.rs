@@ -0,0 +1,226 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::TokenBody,
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests "\r\n" and lone "\r" line endings in text. MixedNewline warning
+// is returned only for the first Newline, that differs from the first one.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_newline::tokenizer_newline_test_01 -- --nocapture
+#[test]
+fn tokenizer_newline_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a\r\nb\nc\rd\n".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 3, pos_zero: 3, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 1, pos_region: 4, pos_zero: 4, length: 1
+      })),
+      Token::Warning(ParseError::MixedNewline(Source {
+         pos_zero: 4,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 18,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 2, pos_line: 0, pos_region: 5, pos_zero: 5, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 2, pos_line: 1, pos_region: 6, pos_zero: 6, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 3, pos_line: 0, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 3, pos_line: 1, pos_region: 8, pos_zero: 8, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests "\r\n" between instruction name and parenthesis, within
+// instruction arguments and within open tag. "\r" is not a part of
+// WhiteSpace.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_newline::tokenizer_newline_test_02 -- --nocapture
+#[test]
+fn tokenizer_newline_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@include \r\n(a\r\nb)\r\n<a \r\nb='x'>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+      })),
+      Token::Real(TokenBody::WhiteSpaceTr(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 2
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 1, pos_region: 12, pos_zero: 12, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 2, pos_region: 13, pos_zero: 13, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 2, pos_line: 0, pos_region: 15, pos_zero: 15, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 2, pos_line: 1, pos_region: 16, pos_zero: 16, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 2, pos_line: 2, pos_region: 17, pos_zero: 17, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 3, pos_line: 0, pos_region: 19, pos_zero: 19, length: 2
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 3, pos_line: 2, pos_region: 21, pos_zero: 21, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 3, pos_line: 3, pos_region: 22, pos_zero: 22, length: 2
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 4, pos_line: 0, pos_region: 24, pos_zero: 24, length: 1
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 4, pos_line: 1, pos_region: 25, pos_zero: 25, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 4, pos_line: 2, pos_region: 26, pos_zero: 26, length: 1
+      })),
+      Token::Real(TokenBody::AttrValue(Span {
+         index: 0, line: 4, pos_line: 3, pos_region: 27, pos_zero: 27, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 4, pos_line: 4, pos_region: 28, pos_zero: 28, length: 1
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 4, pos_line: 5, pos_region: 29, pos_zero: 29, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests comment, that is not closed and region ends with "\r\n". Newline
+// must envelop both bytes.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_newline::tokenizer_newline_test_03 -- --nocapture
+#[test]
+fn tokenizer_newline_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "{{-- x\r\ny\r\n".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 6
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 2
+      })),
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 1, pos_region: 9, pos_zero: 9, length: 2
+      })),
+      Token::Error(ParseError::OpenComment(Source {
+         pos_zero: 0,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 10,
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests instruction after text, that has "\r\n" between instruction name
+// and parenthesis.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_newline::tokenizer_newline_test_04 -- --nocapture
+#[test]
+fn tokenizer_newline_test_04() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a@include\r\n(x)".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 8
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 2
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 1, pos_region: 12, pos_zero: 12, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 1, pos_line: 2, pos_region: 13, pos_zero: 13, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -0,0 +1,96 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::{TokenBody, TextContext},
+   span::Span,
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests that "<" within script is not a tag start and interpolation
+// within script is tagged with TextContext::Script.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_raw_text::tokenizer_raw_text_test_01 -- --nocapture
+#[test]
+fn tokenizer_raw_text_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<script>if (a<b) {{ x }}</script>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 7
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 9
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 17, pos_region: 17, pos_zero: 17, length: 2
+      }, TextContext::Script)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 19, pos_region: 19, pos_zero: 19, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 0, pos_line: 22, pos_region: 22, pos_zero: 22, length: 2
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 0, pos_line: 24, pos_region: 24, pos_zero: 24, length: 9
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests that only closing tag for the same element ends raw text and
+// that element name is matched ignoring character case.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_raw_text::tokenizer_raw_text_test_02 -- --nocapture
+#[test]
+fn tokenizer_raw_text_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<TITLE>a</b></title >c".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 6
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 5
+      })),
+      Token::Real(TokenBody::TagCloseStart(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 7
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 19, pos_region: 19, pos_zero: 19, length: 1
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 0, pos_line: 20, pos_region: 20, pos_zero: 20, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 21, pos_region: 21, pos_zero: 21, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -0,0 +1,56 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::TokenBody,
+   parse_error::ParseError,
+};
+
+
+
+// This tests region release. Included region is released only when it is
+// tokenized completely and consumer is done with its Tokens, kept Tokens hold
+// it back. Spans into released region are refused, root region is never
+// released.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_release::tokenizer_release_test_01 -- --nocapture
+#[test]
+fn tokenizer_release_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "abc\n".into());
+      t.src_push(None, "xyz".into());
+   }
+
+   let checkpoint = t.checkpoint().expect("Checkpoint must be made.");
+
+   let span_included = match t.next() {
+      Some(Token::Real(TokenBody::Defered(span))) => span,
+      got => panic!("Defered must be tokenized, got: {:?}", got),
+   };
+
+   let span_root = match t.next() {
+      Some(Token::Real(TokenBody::Defered(span))) => span,
+      got => panic!("Defered must be tokenized, got: {:?}", got),
+   };
+
+   assert_eq!(t.region_release(2), 0);
+   assert_eq!(t.region_release(3), 0);
+
+   assert_eq!(t.rewind(checkpoint), Ok(()));
+   t.checkpoint_release();
+   t.next();
+   assert_eq!(t.region_release(3), 1);
+   assert_eq!(t.region_release(3), 0);
+
+   match t.span_slice(&span_included) {
+      Err(Token::Error(ParseError::RegionReleased(source))) => {
+         assert_eq!(source.code, 26);
+         assert_eq!(source.pos_zero, 0);
+      }
+      got => panic!("Span must be refused, got: {:?}", got),
+   }
+
+   while t.next().is_some() {}
+   assert_eq!(t.region_release(usize::MAX), 0);
+   assert_eq!(t.span_slice(&span_root), Ok(&b"abc"[..]));
+}
@@ -0,0 +1,85 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::TokenBody,
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests Tokenizer reuse after reset. Span and checkpoint from previous
+// generation are refused, pos_zero continues from where previous generation
+// ended and warning counter starts from 0 again.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_reset::tokenizer_reset_test_01 -- --nocapture
+#[test]
+fn tokenizer_reset_test_01() {
+   let mut t = Tokenizer::new();
+   t.warning_limit_set(1);
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a < b\n".into());
+   }
+
+   let span = match t.next() {
+      Some(Token::Real(TokenBody::Defered(span))) => span,
+      got => panic!("Defered must be tokenized, got: {:?}", got),
+   };
+
+   let checkpoint = t.checkpoint().expect("Checkpoint must be made.");
+   while t.next().is_some() {}
+
+   t.reset();
+   assert_eq!(t.generation(), 1);
+   assert_eq!(t.generation_pos_zero(), 6);
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "c > d".into());
+   }
+
+   match t.span_slice(&span) {
+      Err(Token::Error(ParseError::RegionReleased(source))) => {
+         assert_eq!(source.code, 27);
+      }
+      got => panic!("Span must be refused, got: {:?}", got),
+   }
+
+   t.checkpoint().expect("Checkpoint must be made.");
+   match t.rewind(checkpoint) {
+      Err(Token::Error(ParseError::LookaheadError(source))) => {
+         assert_eq!(source.code, 24);
+      }
+      got => panic!("Rewind must be refused, got: {:?}", got),
+   }
+   t.checkpoint_release();
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 6, length: 2
+      })),
+      Token::Real(TokenBody::Gt(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 8, length: 1
+      })),
+      Token::Warning(ParseError::UnescapedGt(Source {
+         pos_zero: 8,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 6,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 9, length: 2
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -0,0 +1,90 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::TokenBody,
+   span::Span,
+};
+
+use super::scan::ByteSet;
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests ByteSet.find for special byte at every position around vector
+// chunk boundaries, and for source without special bytes. Result must be the
+// same as with plain byte by byte search.
+// cargo test tokenizer::test_scan::tokenizer_scan_test_01 -- --nocapture
+#[test]
+fn tokenizer_scan_test_01() {
+   let set = ByteSet::new(&[0x0A, 0x0D, 0x3C, 0x3E, 0x40, 0x7B, 0x7B]);
+
+   for len in 0..50 {
+      for pos_special in 0..len {
+         for special in [0x0A, 0x3C, 0x40, 0x7B] {
+            let mut src = vec![0x61 /* a */; len];
+            src[pos_special] = special;
+
+            for pos in 0..len {
+               let expect = match pos <= pos_special {
+                  true => pos_special,
+                  false => len,
+               };
+               assert_eq!(set.find(&src, pos), expect,
+                  "len: {}, pos: {}, special at: {}", len, pos, pos_special
+               );
+            }
+         }
+      }
+
+      let src = vec![0xFF; len];
+      assert_eq!(set.find(&src, 0), len);
+   }
+}
+
+
+
+// This tests text and instruction arguments, that are longer than vector chunk,
+// thus special bytes are found by vectorized scanning.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_scan::tokenizer_scan_test_02 -- --nocapture
+#[test]
+fn tokenizer_scan_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, concat!(
+         "Lorem ipsum dolor sit amet, consectetur ",
+         "@include(lorem_ipsum_dolor_sit_amet/consectetur.html)\n",
+         "adipiscing elit",
+      ).into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 40
+      })),
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 40, pos_region: 40, pos_zero: 40, length: 8
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 48, pos_region: 48, pos_zero: 48, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 49, pos_region: 49, pos_zero: 49, length: 43
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 92, pos_region: 92, pos_zero: 92, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 93, pos_region: 93, pos_zero: 93, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 94, pos_zero: 94, length: 15
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -1,5 +1,7 @@
 use crate::tokenizer::Tokenizer;
-use crate::span::Span;
+use crate::span::{Span, SpanColumns};
+use crate::token::Token;
+use crate::tokenbody::TokenBody;
 
 // cargo test tokenizer::test_span::tokenizer_slice_test_01 -- --nocapture
 #[test]
@@ -19,7 +21,7 @@ fn tokenizer_slice_test_01() {
       index: 0, length: 5, pos_region: 2, pos_line: 2, pos_zero: 2, line: 0
    };
 
-   if let Some(slice) = t.span_slice(&span1) {
+   if let Ok(slice) = t.span_slice(&span1) {
       let slicestr = String::from_utf8(slice.to_vec()).expect("Invalid utf-8 string.");
       assert_eq!(slicestr, "PASS0");
    }
@@ -31,11 +33,93 @@ fn tokenizer_slice_test_01() {
       index: 1, length: 5, pos_region: 3, pos_line: 3, pos_zero: 5, line: 0
    };
 
-   if let Some(slice) = t.span_slice(&span2) {
+   if let Ok(slice) = t.span_slice(&span2) {
       let slicestr = String::from_utf8(slice.to_vec()).expect("Invalid utf-8 string.");
       assert_eq!(slicestr, "PASS1");
    }
    else {
       panic!("Could not create slice1 from bytes.");
    }
-}
\ No newline at end of file
+}
+
+
+// This tests character columns and display width for Spans in line with
+// multi-byte characters, tab and wide characters.
+// cargo test tokenizer::test_span::tokenizer_columns_test_01 -- --nocapture
+#[test]
+fn tokenizer_columns_test_01() {
+   let mut t = Tokenizer::new();
+
+   if let Err(e) = t.src_push(None, "x\nĀbols\t🙂 <b>日本</b>".into()){
+      panic!("Expected Ok(None), got: Err({:?})", e);
+   }
+
+   // "<b>" after "Ābols\t🙂 ".
+   let span = Span {
+      index: 0, length: 3, pos_region: 14, pos_line: 12, pos_zero: 14, line: 1
+   };
+
+   let expect = SpanColumns {
+      column: 8, length: 3, column_width: 11, width: 3
+   };
+   assert_eq!(t.span_columns(&span), Some(expect));
+
+   // "日本" with tab width 2.
+   t.tab_width_set(2);
+   let span = Span {
+      index: 0, length: 6, pos_region: 17, pos_line: 15, pos_zero: 17, line: 1
+   };
+
+   let expect = SpanColumns {
+      column: 11, length: 2, column_width: 12, width: 4
+   };
+   assert_eq!(t.span_columns(&span), Some(expect));
+
+   // Out of bounds.
+   let span = Span {
+      index: 0, length: 30, pos_region: 17, pos_line: 15, pos_zero: 17, line: 1
+   };
+   assert_eq!(t.span_columns(&span), None);
+}
+
+
+
+// This tests borrowed source, that is tokenized in place. Tokens must be the
+// same as for owned source, span_slice must return slice of borrowed source
+// itself, not of a copy, and UTF-8 BOM must be skipped without copying.
+// cargo test tokenizer::test_span::tokenizer_slice_test_02 -- --nocapture
+#[test]
+fn tokenizer_slice_test_02() {
+   let src: &[u8] = b"\xEF\xBB\xBFHi {{ name }}!\n@include(x)";
+
+   let mut t = Tokenizer::new();
+   if let Err(e) = t.src_push(None, src.to_vec()){
+      panic!("Expected Ok(None), got: Err({:?})", e);
+   }
+   let expect: Vec<Token> = (&mut t).collect();
+
+   let mut t = Tokenizer::new();
+   if let Err(e) = t.src_push_borrowed(None, src){
+      panic!("Expected Ok(None), got: Err({:?})", e);
+   }
+   let tokens: Vec<Token> = (&mut t).collect();
+   assert_eq!(tokens, expect);
+
+   // " name " after BOM and "Hi {{".
+   let span = match tokens.get(2) {
+      Some(Token::Real(TokenBody::Expression(span))) => *span,
+      token => panic!("Expected Expression, got: {:?}", token),
+   };
+
+   match t.span_slice(&span) {
+      Ok(slice) => {
+         assert_eq!(slice, b" name ");
+         assert_eq!(slice.as_ptr(), src[8..].as_ptr());
+      }
+      Err(..) => panic!("Could not create slice from borrowed source."),
+   }
+
+   let out = format!("{:?}", tokens[2].fmt(&t));
+   let pass = "Real(Expression(Span { index: 0, length: 6, pos_region: 5, pos_line: 5, pos_zero: 5, line: 0, text: \" name \" }))";
+   assert_eq!(out.as_str(), pass);
+}
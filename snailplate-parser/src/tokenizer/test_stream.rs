@@ -0,0 +1,208 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::TokenBody,
+   span::Span,
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// Collect all tokens that Tokenizer can return at the moment, StateChange is
+// ignored.
+fn tokenizer_drain(t: &mut Tokenizer, tokens: &mut Vec<Token>) {
+   for token in t {
+      if let Token::StateChange = token {
+         continue;
+      }
+
+      tokens.push(token);
+   }
+}
+
+
+
+// This tests instruction and comment, that straddle appended chunks. Nothing
+// is returned for the last line, untill it is complete.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_stream::tokenizer_stream_test_01 -- --nocapture
+#[test]
+fn tokenizer_stream_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push_open(None, "a @incl".into());
+   }
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &[], true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+
+   #[allow(unused_must_use)] {
+      t.src_append(b"ude(x)\n{{");
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 8
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+
+   #[allow(unused_must_use)] {
+      t.src_append(b"-- c --}}b");
+      t.src_close();
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 14, pos_zero: 14, length: 11
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 11, pos_region: 25, pos_zero: 25, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests that source appended in chunks of any size is tokenized the same
+// way as if it was pushed at once.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_stream::tokenizer_stream_test_02 -- --nocapture
+#[test]
+fn tokenizer_stream_test_02() {
+   let src = "<div class=\"a\n{{ b }}\" @if(x)>\n  {{-- c\n d --}}\n\
+      @include (\n 'e' )@endif\n<script>x < y</script>@verbatim\n@if(\n\
+      @endverbatim{!! f\n !!}<!-- g -->@inclu\nde @@ h@b.c </p '>@i\n\
+      <p\n";
+
+   let mut t = Tokenizer::new();
+   let mut expect = Vec::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, src.into());
+   }
+   tokenizer_drain(&mut t, &mut expect);
+
+   for len_chunk in 1..8 {
+      let mut t = Tokenizer::new();
+      let mut got = Vec::new();
+
+      #[allow(unused_must_use)] {
+         t.src_push_open(None, Vec::new());
+      }
+
+      for chunk in src.as_bytes().chunks(len_chunk) {
+         #[allow(unused_must_use)] {
+            t.src_append(chunk);
+         }
+         tokenizer_drain(&mut t, &mut got);
+      }
+
+      #[allow(unused_must_use)] {
+         t.src_close();
+      }
+      tokenizer_drain(&mut t, &mut got);
+
+      assert_eq!(expect, got, "chunk length: {}", len_chunk);
+   }
+}
+
+
+
+// This tests stream with lone "\r" line endings. Lines are returned as soon as
+// they are complete, "\r" at the end of appended bytes is held back till the
+// next byte tells that it is not "\r\n".
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_stream::tokenizer_stream_test_03 -- --nocapture
+#[test]
+fn tokenizer_stream_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push_open(None, "a\rb\r".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+
+   #[allow(unused_must_use)] {
+      t.src_append(b"c\rd");
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 1, pos_region: 3, pos_zero: 3, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 2, pos_line: 0, pos_region: 4, pos_zero: 4, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 2, pos_line: 1, pos_region: 5, pos_zero: 5, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+
+   #[allow(unused_must_use)] {
+      t.src_close();
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 3, pos_line: 0, pos_region: 6, pos_zero: 6, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
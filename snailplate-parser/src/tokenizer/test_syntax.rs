@@ -0,0 +1,185 @@
+use crate::{
+   tokenizer::{Tokenizer, Syntax},
+   token::Token,
+   tokenbody::{TokenBody, TextContext},
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests custom sigil and interpolation delimiters. Default "@" and "{{"
+// have no special meaning then.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_syntax::tokenizer_syntax_test_01 -- --nocapture
+#[test]
+fn tokenizer_syntax_test_01() {
+   let syntax = match Syntax::custom(b'%', ("[[", "]]"), ("[!!", "!!]")) {
+      Ok(syntax) => syntax,
+      Err(token) => panic!("Syntax must be valid, got: {:?}", token),
+   };
+
+   let mut t = Tokenizer::with_syntax(syntax);
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "%include(a)[[ x ]]@if {{ y }}".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 2
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 0, pos_line: 16, pos_region: 16, pos_zero: 16, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 18, pos_region: 18, pos_zero: 18, length: 11
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests escaped custom sigil, template comment built from custom
+// delimiters and raw interpolation within tag.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_syntax::tokenizer_syntax_test_02 -- --nocapture
+#[test]
+fn tokenizer_syntax_test_02() {
+   let syntax = match Syntax::custom(b'%', ("[[", "]]"), ("[!!", "!!]")) {
+      Ok(syntax) => syntax,
+      Err(token) => panic!("Syntax must be valid, got: {:?}", token),
+   };
+
+   let mut t = Tokenizer::with_syntax(syntax);
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a%%b [[-- %if --]]<p class=\"[!! c !!]\">".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::EscapedAt(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 2
+      })),
+      Token::Real(TokenBody::Comment(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 13
+      })),
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 18, pos_region: 18, pos_zero: 18, length: 2
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 20, pos_region: 20, pos_zero: 20, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 21, pos_region: 21, pos_zero: 21, length: 5
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 0, pos_line: 26, pos_region: 26, pos_zero: 26, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 27, pos_region: 27, pos_zero: 27, length: 1
+      })),
+      Token::Real(TokenBody::RawInterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 28, pos_region: 28, pos_zero: 28, length: 3
+      }, TextContext::Tag)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 31, pos_region: 31, pos_zero: 31, length: 3
+      })),
+      Token::Real(TokenBody::RawInterpolationClose(Span {
+         index: 0, line: 0, pos_line: 34, pos_region: 34, pos_zero: 34, length: 3
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 37, pos_region: 37, pos_zero: 37, length: 1
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 38, pos_region: 38, pos_zero: 38, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests Syntax validation. Default delimiters are valid, invalid sigil,
+// invalid delimiters and ambiguous open delimiters are refused. "-" is
+// refused as well, since it is trim marker.
+// cargo test tokenizer::test_syntax::tokenizer_syntax_test_03 -- --nocapture
+#[test]
+fn tokenizer_syntax_test_03() {
+   let syntax = Syntax::custom(b'@', ("{{", "}}"), ("{!!", "!!}"));
+   assert_eq!(syntax, Ok(Syntax::new()));
+
+   let cases = [
+      (b'a', ("[[", "]]"), ("[!!", "!!]"), 19),
+      (b'<', ("[[", "]]"), ("[!!", "!!]"), 19),
+      (b'-', ("[[", "]]"), ("[!!", "!!]"), 19),
+      (b'%', ("", "]]"), ("[!!", "!!]"), 20),
+      (b'%', ("[[", "]]"), ("[!!", "!%]"), 20),
+      (b'%', ("<%", "%>"), ("[!!", "!!]"), 20),
+      (b'%', ("[-", "-]"), ("[!!", "!!]"), 20),
+      (b'%', ("[[", "]]]]]]]]]"), ("[!!", "!!]"), 20),
+      (b'%', ("[[", "]]"), ("[[!", "!]]"), 21),
+   ];
+
+   for (sigil, interpolation, raw_interpolation, code) in cases {
+      let expect = Token::Error(ParseError::InvalidDelimiter(Source {
+         pos_zero: 0,
+         component: Component::Tokenizer,
+         line: 0,
+         code: code,
+      }));
+
+      match Syntax::custom(sigil, interpolation, raw_interpolation) {
+         Ok(syntax) => panic!("Syntax must be refused, got: {:?}", syntax),
+         Err(token) => {
+            // Source line is not compared, it changes with code.
+            let got = match token {
+               Token::Error(ParseError::InvalidDelimiter(source)) => {
+                  Token::Error(ParseError::InvalidDelimiter(Source {
+                     line: 0,
+                     ..source
+                  }))
+               }
+               token => token,
+            };
+            assert_eq!(got, expect);
+         }
+      }
+   }
+}
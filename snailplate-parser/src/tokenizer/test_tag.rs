@@ -0,0 +1,493 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::{TokenBody, TextContext},
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests the simplest tag open and close case, without any attributes.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_01 -- --nocapture
+#[test]
+fn tokenizer_tag_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<div>X</div>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 4
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 1
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 6
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests tag with attributes, where attribute value contains ">" and "/>"
+// in quotes. Those must not end the tag.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_02 -- --nocapture
+#[test]
+fn tokenizer_tag_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "A <a title='x > y' b=\"/>\">".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 2
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 5
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::AttrValue(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 5
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 17, pos_region: 17, pos_zero: 17, length: 1
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 18, pos_region: 18, pos_zero: 18, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 19, pos_region: 19, pos_zero: 19, length: 1
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 0, pos_line: 20, pos_region: 20, pos_zero: 20, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 21, pos_region: 21, pos_zero: 21, length: 1
+      })),
+      Token::Real(TokenBody::AttrValue(Span {
+         index: 0, line: 0, pos_line: 22, pos_region: 22, pos_zero: 22, length: 2
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 24, pos_region: 24, pos_zero: 24, length: 1
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 25, pos_region: 25, pos_zero: 25, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests self-closing tag and "</>" close.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_03 -- --nocapture
+#[test]
+fn tokenizer_tag_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<br/><x-y a></>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      })),
+      Token::Real(TokenBody::TagSelfClose(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 4
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 3
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests closing tag that is split over lines, and "<" that can not start
+// a tag. Such "<" is returned as Lt with a warning.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_04 -- --nocapture
+#[test]
+fn tokenizer_tag_test_04() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a < b</p\n>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::Lt(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Warning(ParseError::UnescapedLt(Source {
+         pos_zero: 2,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 5,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 2
+      })),
+      Token::Real(TokenBody::TagCloseStart(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 3
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 9, pos_zero: 9, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests tag that is never closed.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_05 -- --nocapture
+#[test]
+fn tokenizer_tag_test_05() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "X<div a='>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 4
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 1
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::AttrValue(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Error(ParseError::OpenTag(Source {
+         pos_zero: 1,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 3,
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests instruction within tag. After instruction is closed, Tokenizer
+// must return to tag tokenization.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_06 -- --nocapture
+#[test]
+fn tokenizer_tag_test_06() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<p @include(x)>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Real(TokenBody::Include(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 8
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 1
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 1
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 14, pos_region: 14, pos_zero: 14, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests unmatched ">" in text and "<" at the very start of input.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_07 -- --nocapture
+#[test]
+fn tokenizer_tag_test_07() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<3 <b>x</b> 2>1".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Lt(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Warning(ParseError::UnescapedLt(Source {
+         pos_zero: 0,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 5,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 1
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 4
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 2
+      })),
+      Token::Real(TokenBody::Gt(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 1
+      })),
+      Token::Warning(ParseError::UnescapedGt(Source {
+         pos_zero: 13,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 6,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 14, pos_region: 14, pos_zero: 14, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests unquoted attribute values, whitespace around "=", interpolation
+// as unquoted value and newline within quoted value.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_08 -- --nocapture
+#[test]
+fn tokenizer_tag_test_08() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<a href=x b = \"c\nd\" e={{f}}>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 4
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::AttrValue(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 1
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 1
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 13, pos_region: 13, pos_zero: 13, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 0, pos_line: 14, pos_region: 14, pos_zero: 14, length: 1
+      })),
+      Token::Real(TokenBody::AttrValue(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 16, pos_region: 16, pos_zero: 16, length: 1
+      })),
+      Token::Real(TokenBody::AttrValue(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 17, pos_zero: 17, length: 1
+      })),
+      Token::Real(TokenBody::AttrQuote(Span {
+         index: 0, line: 1, pos_line: 1, pos_region: 18, pos_zero: 18, length: 1
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 1, pos_line: 2, pos_region: 19, pos_zero: 19, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 1, pos_line: 3, pos_region: 20, pos_zero: 20, length: 1
+      })),
+      Token::Real(TokenBody::AttrEq(Span {
+         index: 0, line: 1, pos_line: 4, pos_region: 21, pos_zero: 21, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 1, pos_line: 5, pos_region: 22, pos_zero: 22, length: 2
+      }, TextContext::Tag)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 1, pos_line: 7, pos_region: 24, pos_zero: 24, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 1, pos_line: 8, pos_region: 25, pos_zero: 25, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 1, pos_line: 10, pos_region: 27, pos_zero: 27, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests self-closing raw-text element. "/>" is TagSelfClose, not TagClose
+// of "</script>" nor TagOpenEnd, thus tags after it are tokenized as tags, not
+// as content of raw-text element.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_tag::tokenizer_tag_test_09 -- --nocapture
+#[test]
+fn tokenizer_tag_test_09() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<script a /><b>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 7
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::AttrName(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Real(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::TagSelfClose(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 12, length: 2
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 14, pos_region: 14, pos_zero: 14, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
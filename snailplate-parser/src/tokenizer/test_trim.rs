@@ -0,0 +1,358 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::{TokenBody, TextContext},
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+      Suggestion,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests trim markers on interpolation. Leading whitespace before "{{-"
+// and trailing whitespace with newline after "-}}" are Phantom, thus line
+// with only interpolation does not leave blank line.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_trim::tokenizer_trim_test_01 -- --nocapture
+#[test]
+fn tokenizer_trim_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<ul>\n   {{- x -}}  \n</ul>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 1
+      })),
+      Token::Phantom(TokenBody::WhiteSpaceLd(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 5, pos_zero: 5, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 1, pos_line: 3, pos_region: 8, pos_zero: 8, length: 3
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 1, pos_line: 6, pos_region: 11, pos_zero: 11, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 1, pos_line: 9, pos_region: 14, pos_zero: 14, length: 3
+      })),
+      Token::Phantom(TokenBody::WhiteSpaceTr(Span {
+         index: 0, line: 1, pos_line: 12, pos_region: 17, pos_zero: 17, length: 2
+      })),
+      Token::Phantom(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 14, pos_region: 19, pos_zero: 19, length: 1
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 2, pos_line: 0, pos_region: 20, pos_zero: 20, length: 5
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests trim marker on instructions with and without arguments.
+// Whitespace before "@-" is trimmed back to text, whitespace after closing
+// parenthesis or instruction name is trimmed with newline, "\r\n" as well.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_trim::tokenizer_trim_test_02 -- --nocapture
+#[test]
+fn tokenizer_trim_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a  @-if($x)\r\n  b @-endif  \nc".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 1
+      })),
+      Token::Phantom(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 1, pos_region: 1, pos_zero: 1, length: 2
+      })),
+      Token::Real(TokenBody::If(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 4
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 2
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+      })),
+      Token::Phantom(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 2
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 13, pos_zero: 13, length: 3
+      })),
+      Token::Phantom(TokenBody::WhiteSpace(Span {
+         index: 0, line: 1, pos_line: 3, pos_region: 16, pos_zero: 16, length: 1
+      })),
+      Token::Real(TokenBody::EndIf(Span {
+         index: 0, line: 1, pos_line: 4, pos_region: 17, pos_zero: 17, length: 7
+      })),
+      Token::Phantom(TokenBody::WhiteSpaceTr(Span {
+         index: 0, line: 1, pos_line: 11, pos_region: 24, pos_zero: 24, length: 2
+      })),
+      Token::Phantom(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 13, pos_region: 26, pos_zero: 26, length: 1
+      })),
+      Token::Warning(ParseError::MixedNewline(Source {
+         pos_zero: 26,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 18,
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 2, pos_line: 0, pos_region: 27, pos_zero: 27, length: 1
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests what is not trim marker. "{{-}}" has marker only in open
+// delimiter, "-" in expression and "@-" without instruction name are left
+// as they are.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_trim::tokenizer_trim_test_03 -- --nocapture
+#[test]
+fn tokenizer_trim_test_03() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "{{-}} {!!- a-b -!!}user@-x @-foo(1)".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 3
+      }, TextContext::Text)),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 0, pos_line: 3, pos_region: 3, pos_zero: 3, length: 2
+      })),
+      Token::Phantom(TokenBody::WhiteSpace(Span {
+         index: 0, line: 0, pos_line: 5, pos_region: 5, pos_zero: 5, length: 1
+      })),
+      Token::Real(TokenBody::RawInterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 6, pos_region: 6, pos_zero: 6, length: 4
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 5
+      })),
+      Token::Real(TokenBody::RawInterpolationClose(Span {
+         index: 0, line: 0, pos_line: 15, pos_region: 15, pos_zero: 15, length: 4
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 19, pos_region: 19, pos_zero: 19, length: 4
+      })),
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 23, pos_region: 23, pos_zero: 23, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 24, pos_region: 24, pos_zero: 24, length: 3
+      })),
+      Token::Real(TokenBody::UnescapedAt(Span {
+         index: 0, line: 0, pos_line: 27, pos_region: 27, pos_zero: 27, length: 1
+      })),
+      Token::Warning(ParseError::InstructionNearMiss(Source {
+         pos_zero: 27,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 8,
+      }, Suggestion::EscapeAt)),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 28, pos_region: 28, pos_zero: 28, length: 7
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests trim markers within raw-text elements. Interpolation and
+// instruction lines in "<script>" and "<style>" do not leave blank lines,
+// the same way as in text.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_trim::tokenizer_trim_test_04 -- --nocapture
+#[test]
+fn tokenizer_trim_test_04() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<script>\n  {{- x -}}\n  </script><style>\n  @-if($x)\n</style>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 7
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 0, pos_line: 7, pos_region: 7, pos_zero: 7, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+      })),
+      Token::Phantom(TokenBody::WhiteSpaceLd(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 9, pos_zero: 9, length: 2
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 1, pos_line: 2, pos_region: 11, pos_zero: 11, length: 3
+      }, TextContext::Script)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 1, pos_line: 5, pos_region: 14, pos_zero: 14, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 1, pos_line: 8, pos_region: 17, pos_zero: 17, length: 3
+      })),
+      Token::Phantom(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 11, pos_region: 20, pos_zero: 20, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 2, pos_line: 0, pos_region: 21, pos_zero: 21, length: 2
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 2, pos_line: 2, pos_region: 23, pos_zero: 23, length: 9
+      })),
+      Token::Real(TokenBody::TagOpenStart(Span {
+         index: 0, line: 2, pos_line: 11, pos_region: 32, pos_zero: 32, length: 6
+      })),
+      Token::Real(TokenBody::TagOpenEnd(Span {
+         index: 0, line: 2, pos_line: 17, pos_region: 38, pos_zero: 38, length: 1
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 2, pos_line: 18, pos_region: 39, pos_zero: 39, length: 1
+      })),
+      Token::Phantom(TokenBody::WhiteSpaceLd(Span {
+         index: 0, line: 3, pos_line: 0, pos_region: 40, pos_zero: 40, length: 2
+      })),
+      Token::Real(TokenBody::If(Span {
+         index: 0, line: 3, pos_line: 2, pos_region: 42, pos_zero: 42, length: 4
+      })),
+      Token::Real(TokenBody::OpenParen(Span {
+         index: 0, line: 3, pos_line: 6, pos_region: 46, pos_zero: 46, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 3, pos_line: 7, pos_region: 47, pos_zero: 47, length: 2
+      })),
+      Token::Real(TokenBody::CloseParen(Span {
+         index: 0, line: 3, pos_line: 9, pos_region: 49, pos_zero: 49, length: 1
+      })),
+      Token::Phantom(TokenBody::Newline(Span {
+         index: 0, line: 3, pos_line: 10, pos_region: 50, pos_zero: 50, length: 1
+      })),
+      Token::Real(TokenBody::TagClose(Span {
+         index: 0, line: 4, pos_line: 0, pos_region: 51, pos_zero: 51, length: 8
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests trim markers within HTML comment and CDATA section, where
+// instructions and interpolations are tokenized as in text.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_trim::tokenizer_trim_test_05 -- --nocapture
+#[test]
+fn tokenizer_trim_test_05() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "<!--\n  @-endif \n  {{- x }}--><![CDATA[{{ y -}}\n]]>".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::HtmlCommentOpen(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 4
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 4, pos_region: 4, pos_zero: 4, length: 1
+      })),
+      Token::Phantom(TokenBody::WhiteSpaceLd(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 5, pos_zero: 5, length: 2
+      })),
+      Token::Real(TokenBody::EndIf(Span {
+         index: 0, line: 1, pos_line: 2, pos_region: 7, pos_zero: 7, length: 7
+      })),
+      Token::Phantom(TokenBody::WhiteSpaceTr(Span {
+         index: 0, line: 1, pos_line: 9, pos_region: 14, pos_zero: 14, length: 1
+      })),
+      Token::Phantom(TokenBody::Newline(Span {
+         index: 0, line: 1, pos_line: 10, pos_region: 15, pos_zero: 15, length: 1
+      })),
+      Token::Phantom(TokenBody::WhiteSpaceLd(Span {
+         index: 0, line: 2, pos_line: 0, pos_region: 16, pos_zero: 16, length: 2
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 2, pos_line: 2, pos_region: 18, pos_zero: 18, length: 3
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 2, pos_line: 5, pos_region: 21, pos_zero: 21, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 2, pos_line: 8, pos_region: 24, pos_zero: 24, length: 2
+      })),
+      Token::Real(TokenBody::HtmlCommentClose(Span {
+         index: 0, line: 2, pos_line: 10, pos_region: 26, pos_zero: 26, length: 3
+      })),
+      Token::Real(TokenBody::CdataOpen(Span {
+         index: 0, line: 2, pos_line: 13, pos_region: 29, pos_zero: 29, length: 9
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 2, pos_line: 22, pos_region: 38, pos_zero: 38, length: 2
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 2, pos_line: 24, pos_region: 40, pos_zero: 40, length: 3
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 2, pos_line: 27, pos_region: 43, pos_zero: 43, length: 3
+      })),
+      Token::Phantom(TokenBody::Newline(Span {
+         index: 0, line: 2, pos_line: 30, pos_region: 46, pos_zero: 46, length: 1
+      })),
+      Token::Real(TokenBody::CdataClose(Span {
+         index: 0, line: 3, pos_line: 0, pos_region: 47, pos_zero: 47, length: 3
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
@@ -0,0 +1,96 @@
+use crate::{
+   tokenizer::Tokenizer,
+   token::Token,
+   tokenbody::{TokenBody, TextContext},
+   span::Span,
+   parse_error::{
+      ParseError,
+      Source,
+      Component,
+   },
+};
+
+use super::tokenlist_match_or_fail;
+
+
+
+// This tests that instructions, tags and interpolations within verbatim block
+// are returned as Defered. After "@endverbatim" Tokenizer must continue as
+// usual.
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_verbatim::tokenizer_verbatim_test_01 -- --nocapture
+#[test]
+fn tokenizer_verbatim_test_01() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "a @verbatim<b @click=\"x\">{{ y }}@endverbatim{{z}}".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 2
+      })),
+      Token::Real(TokenBody::Verbatim(Span {
+         index: 0, line: 0, pos_line: 2, pos_region: 2, pos_zero: 2, length: 9
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 11, length: 21
+      })),
+      Token::Real(TokenBody::EndVerbatim(Span {
+         index: 0, line: 0, pos_line: 32, pos_region: 32, pos_zero: 32, length: 12
+      })),
+      Token::Real(TokenBody::InterpolationOpen(Span {
+         index: 0, line: 0, pos_line: 44, pos_region: 44, pos_zero: 44, length: 2
+      }, TextContext::Text)),
+      Token::Real(TokenBody::Expression(Span {
+         index: 0, line: 0, pos_line: 46, pos_region: 46, pos_zero: 46, length: 1
+      })),
+      Token::Real(TokenBody::InterpolationClose(Span {
+         index: 0, line: 0, pos_line: 47, pos_region: 47, pos_zero: 47, length: 2
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
+
+
+
+// This tests verbatim block that is never closed. Error must point to
+// "@verbatim".
+// cargo test -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose tokenizer::test_verbatim::tokenizer_verbatim_test_02 -- --nocapture
+#[test]
+fn tokenizer_verbatim_test_02() {
+   let mut t = Tokenizer::new();
+
+   #[allow(unused_must_use)] {
+      t.src_push(None, "@verbatim\n{{ x @endverbatimx".into());
+   }
+
+   let list: Vec<Token> = [
+      Token::Real(TokenBody::Verbatim(Span {
+         index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 9
+      })),
+      Token::Real(TokenBody::Newline(Span {
+         index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+      })),
+      Token::Real(TokenBody::Defered(Span {
+         index: 0, line: 1, pos_line: 0, pos_region: 10, pos_zero: 10, length: 18
+      })),
+      Token::Error(ParseError::OpenVerbatim(Source {
+         pos_zero: 0,
+         component: Component::Tokenizer,
+         line: 0,
+         code: 11,
+      })),
+   ].to_vec();
+
+   if let Err((idx, expect, got)) = tokenlist_match_or_fail(&mut t, &list, true){
+      panic!("Token mismatch at idx: {}. Expect: {:?} vs got: {:?}", idx,
+         expect, got
+      );
+   }
+}
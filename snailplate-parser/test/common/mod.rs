@@ -1,3 +1,7 @@
+// This module is shared between integration test binaries and not every test
+// binary uses all helpers.
+#![allow(dead_code)]
+
 use std::{
    collections::HashMap,
    fs::File,
@@ -139,6 +143,13 @@ impl<T: ExpectedHashMap> TokenIntegrationTester for T {
       register!("include_contains_xxx_phantom");
       register!("include_contains_xxx_bad_whitespace_phantom");
       register!("include_contains_xxx_bad_whitespace");
+      register!("include_quoted_contains_xxx_phantom");
+      register!("include_latin1_phantom");
+      register!("include_contains_xxx_bad_whitespace_limit");
+      register!("include_contains_xxx_bad_whitespace_reset");
+      register!("include_twice_contains_xxx_phantom");
+      register!("include_empty");
+      register!("include_newline_path");
    }
 
 
@@ -200,6 +211,21 @@ impl<T: ExpectedHashMap> TokenIntegrationTester for T {
                   (Pe::NoMemory(s1), Pe::NoMemory(s2))
                   | (Pe::InternalError(s1), Pe::InternalError(s2))
                   | (Pe::OpenInstruction(s1), Pe::OpenInstruction(s2))
+                  | (Pe::OpenTag(s1), Pe::OpenTag(s2))
+                  | (Pe::OpenInterpolation(s1), Pe::OpenInterpolation(s2))
+                  | (Pe::OpenComment(s1), Pe::OpenComment(s2))
+                  | (Pe::OpenVerbatim(s1), Pe::OpenVerbatim(s2))
+                  | (Pe::OpenHtmlComment(s1), Pe::OpenHtmlComment(s2))
+                  | (Pe::OpenCdata(s1), Pe::OpenCdata(s2))
+                  | (Pe::StreamError(s1), Pe::StreamError(s2))
+                  | (Pe::LookaheadError(s1), Pe::LookaheadError(s2))
+                  | (Pe::RegionReleased(s1), Pe::RegionReleased(s2))
+                  | (Pe::InvalidUtf8(s1), Pe::InvalidUtf8(s2))
+                  | (Pe::MixedNewline(s1), Pe::MixedNewline(s2))
+                  | (Pe::InvalidDelimiter(s1), Pe::InvalidDelimiter(s2))
+                  | (Pe::OpenString(s1), Pe::OpenString(s2))
+                  | (Pe::UnescapedLt(s1), Pe::UnescapedLt(s2))
+                  | (Pe::UnescapedGt(s1), Pe::UnescapedGt(s2))
                   | (Pe::InstructionError(s1), Pe::InstructionError(s2))
                   | (Pe::InstructionNotOpen(s1), Pe::InstructionNotOpen(s2))
                   | (Pe::InstructionMissingArgs(s1), Pe::InstructionMissingArgs(s2))
@@ -212,6 +238,26 @@ impl<T: ExpectedHashMap> TokenIntegrationTester for T {
                         return Err((idx, Some((*expect).clone()), Some(token)));
                      }
                   }
+                  (Pe::InstructionNearMiss(s1, x1), Pe::InstructionNearMiss(s2, x2))
+                  => {
+                     if s1.pos_zero != s2.pos_zero
+                     || s1.component != s2.component
+                     || s1.code != s2.code
+                     || x1 != x2
+                     {
+                        return Err((idx, Some((*expect).clone()), Some(token)));
+                     }
+                  }
+                  (Pe::ErrorLimit(s1, x1), Pe::ErrorLimit(s2, x2))
+                  => {
+                     if s1.pos_zero != s2.pos_zero
+                     || s1.component != s2.component
+                     || s1.code != s2.code
+                     || x1 != x2
+                     {
+                        return Err((idx, Some((*expect).clone()), Some(token)));
+                     }
+                  }
                   _ => {
                      if *expect != token {
                         return Err((idx, Some((*expect).clone()), Some(token)));
@@ -0,0 +1,11 @@
+[
+   Token::Phantom(TokenBody::Include(Span {
+      index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+   })),
+   Token::Phantom(TokenBody::WhiteSpace(Span {
+      index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 3
+   })),
+   Token::Fatal(ParseError::ErrorLimit(Source {
+      pos_zero: 8, component: Component::IncludeResolver, line: 0, code: 10
+   }, 1)),
+]
@@ -0,0 +1,23 @@
+[
+   Token::Phantom(TokenBody::Include(Span {
+      index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 30, length: 8
+   })),
+   Token::Phantom(TokenBody::WhiteSpace(Span {
+      index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 38, length: 3
+   })),
+   Token::Warning(ParseError::UnwantedWhiteSpace(Source {
+      pos_zero: 38, component: Component::Tokenizer, line: 0, code: 2
+   })),
+   Token::Phantom(TokenBody::OpenParen(Span {
+      index: 0, line: 0, pos_line: 11, pos_region: 11, pos_zero: 41, length: 1
+   })),
+   Token::Phantom(TokenBody::Defered(Span {
+      index: 0, line: 0, pos_line: 12, pos_region: 12, pos_zero: 42, length: 17
+   })),
+   Token::Phantom(TokenBody::CloseParen(Span {
+      index: 0, line: 0, pos_line: 29, pos_region: 29, pos_zero: 59, length: 1
+   })),
+   Token::Real(TokenBody::Defered(Span {
+      index: 1, line: 0, pos_line: 0, pos_region: 0, pos_zero: 60, length: 3
+   })),
+]
\ No newline at end of file
@@ -0,0 +1,20 @@
+[
+   Token::Phantom(TokenBody::Include(Span {
+      index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+   })),
+   Token::Real(TokenBody::OpenParen(Span {
+      index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+   })),
+   Token::Real(TokenBody::CloseParen(Span {
+      index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 1
+   })),
+   Token::Error(ParseError::InstructionMissingArgs(Source {
+      pos_zero: 0,
+      component: Component::IncludeResolver,
+      line: 0,
+      code: 9,
+   })),
+   Token::Real(TokenBody::Defered(Span {
+      index: 0, line: 0, pos_line: 10, pos_region: 10, pos_zero: 10, length: 1
+   })),
+]
\ No newline at end of file
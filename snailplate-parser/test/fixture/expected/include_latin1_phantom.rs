@@ -0,0 +1,29 @@
+[
+   Token::Phantom(TokenBody::Include(Span {
+      index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+   })),
+   Token::Phantom(TokenBody::OpenParen(Span {
+      index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+   })),
+   Token::Phantom(TokenBody::Defered(Span {
+      index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 11
+   })),
+   Token::Phantom(TokenBody::CloseParen(Span {
+      index: 0, line: 0, pos_line: 20, pos_region: 20, pos_zero: 20, length: 1
+   })),
+   Token::Real(TokenBody::Defered(Span {
+      index: 1, line: 0, pos_line: 0, pos_region: 0, pos_zero: 21, length: 4
+   })),
+   Token::Error(ParseError::InvalidUtf8(Source {
+      pos_zero: 24,
+      component: Component::Tokenizer,
+      line: 0,
+      code: 17,
+   })),
+   Token::Real(TokenBody::Newline(Span {
+      index: 1, line: 0, pos_line: 4, pos_region: 4, pos_zero: 25, length: 1
+   })),
+   Token::Real(TokenBody::Defered(Span {
+      index: 0, line: 0, pos_line: 21, pos_region: 21, pos_zero: 26, length: 1
+   })),
+]
@@ -0,0 +1,17 @@
+[
+   Token::Phantom(TokenBody::Include(Span {
+      index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+   })),
+   Token::Phantom(TokenBody::OpenParen(Span {
+      index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+   })),
+   Token::Phantom(TokenBody::StringLiteral(Span {
+      index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 19
+   })),
+   Token::Phantom(TokenBody::CloseParen(Span {
+      index: 0, line: 0, pos_line: 28, pos_region: 28, pos_zero: 28, length: 1
+   })),
+   Token::Real(TokenBody::Defered(Span {
+      index: 1, line: 0, pos_line: 0, pos_region: 0, pos_zero: 29, length: 3
+   })),
+]
\ No newline at end of file
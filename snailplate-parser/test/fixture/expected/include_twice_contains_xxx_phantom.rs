@@ -0,0 +1,35 @@
+[
+   Token::Phantom(TokenBody::Include(Span {
+      index: 0, line: 0, pos_line: 0, pos_region: 0, pos_zero: 0, length: 8
+   })),
+   Token::Phantom(TokenBody::OpenParen(Span {
+      index: 0, line: 0, pos_line: 8, pos_region: 8, pos_zero: 8, length: 1
+   })),
+   Token::Phantom(TokenBody::Defered(Span {
+      index: 0, line: 0, pos_line: 9, pos_region: 9, pos_zero: 9, length: 17
+   })),
+   Token::Phantom(TokenBody::CloseParen(Span {
+      index: 0, line: 0, pos_line: 26, pos_region: 26, pos_zero: 26, length: 1
+   })),
+   Token::Real(TokenBody::Defered(Span {
+      index: 1, line: 0, pos_line: 0, pos_region: 0, pos_zero: 27, length: 3
+   })),
+   Token::Real(TokenBody::Newline(Span {
+      index: 0, line: 0, pos_line: 27, pos_region: 27, pos_zero: 30, length: 1
+   })),
+   Token::Phantom(TokenBody::Include(Span {
+      index: 0, line: 1, pos_line: 0, pos_region: 28, pos_zero: 31, length: 8
+   })),
+   Token::Phantom(TokenBody::OpenParen(Span {
+      index: 0, line: 1, pos_line: 8, pos_region: 36, pos_zero: 39, length: 1
+   })),
+   Token::Phantom(TokenBody::Defered(Span {
+      index: 0, line: 1, pos_line: 9, pos_region: 37, pos_zero: 40, length: 17
+   })),
+   Token::Phantom(TokenBody::CloseParen(Span {
+      index: 0, line: 1, pos_line: 26, pos_region: 54, pos_zero: 57, length: 1
+   })),
+   Token::Real(TokenBody::Defered(Span {
+      index: 2, line: 0, pos_line: 0, pos_region: 0, pos_zero: 58, length: 3
+   })),
+]
\ No newline at end of file
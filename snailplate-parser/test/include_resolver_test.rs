@@ -103,3 +103,104 @@ fn resolver_include_test_02() {
 }
 
 
+
+// cargo test -F dbg_include_resolver_verbose -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose --test include_resolver_test resolver_include_test_03 -- --nocapture
+#[test]
+fn resolver_include_test_03() {
+   let mut tt = ResolverTester::new("include_quoted_contains_xxx",
+      Some("include_quoted_contains_xxx_phantom")
+   );
+   tt.token_test_run();
+}
+
+
+
+// This tests Latin-1 file, that is included by mistake. InvalidUtf8 error is
+// returned right after Token that envelops invalid byte, and tokenization
+// continues.
+// cargo test -F dbg_include_resolver_verbose -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose --test include_resolver_test resolver_include_test_04 -- --nocapture
+#[test]
+fn resolver_include_test_04() {
+   let mut tt = ResolverTester::new("include_latin1",
+      Some("include_latin1_phantom")
+   );
+   tt.token_test_run();
+}
+
+
+
+// This tests warning limit on IncludeResolver. UnwantedWhiteSpace warning from
+// Tokenizer exceeds limit 0, thus ErrorLimit is returned instead and the rest
+// of include is not resolved. ErrorLimit has the position of that warning.
+// cargo test -F dbg_include_resolver_verbose -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose --test include_resolver_test resolver_limit_test_01 -- --nocapture
+#[test]
+fn resolver_limit_test_01() {
+   let mut tt = ResolverTester::new("include_contains_xxx_bad_whitespace",
+      Some("include_contains_xxx_bad_whitespace_limit")
+   );
+   tt.resolver.warning_limit_set(0);
+   tt.token_test_run();
+}
+
+
+
+// This tests IncludeResolver reuse after reset. The first run fails with
+// ErrorLimit, after reset the same template is resolved completely and
+// pos_zero continues from where Tokenizer stopped.
+// cargo test -F dbg_include_resolver_verbose -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose --test include_resolver_test resolver_reset_test_01 -- --nocapture
+#[test]
+fn resolver_reset_test_01() {
+   let mut tt = ResolverTester::new("include_contains_xxx_bad_whitespace",
+      Some("include_contains_xxx_bad_whitespace_limit")
+   );
+   tt.resolver.warning_limit_set(0);
+   tt.token_test_run();
+
+   tt.resolver.reset();
+   tt.resolver.warning_limit_set(usize::MAX);
+   assert_eq!(tt.resolver.tokenizer.generation(), 1);
+   assert_eq!(tt.resolver.tokenizer.generation_pos_zero(), 30);
+
+   if let Err(token) = tt.resolver.file_read("include_contains_xxx_bad_whitespace.html") {
+      panic!("Resolver failed with file reading. Return token: {:?}", token);
+   }
+
+   tt.name_expected = Some("include_contains_xxx_bad_whitespace_reset".to_string());
+   tt.token_test_run();
+}
+
+
+
+// This tests two includes in the same template. Tokenizer must return to the
+// including region after each of them, not to the previously pushed one.
+// cargo test -F dbg_include_resolver_verbose -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose --test include_resolver_test resolver_include_twice_test_01 -- --nocapture
+#[test]
+fn resolver_include_twice_test_01() {
+   let mut tt = ResolverTester::new("include_twice_contains_xxx",
+      Some("include_twice_contains_xxx_phantom")
+   );
+   tt.token_test_run();
+}
+
+
+
+// This tests "@include()". Path is missing, thus batch is returned as-is with
+// InstructionMissingArgs after ')' and tokenization continues.
+// cargo test -F dbg_include_resolver_verbose -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose --test include_resolver_test resolver_include_fail_test_01 -- --nocapture
+#[test]
+fn resolver_include_fail_test_01() {
+   let mut tt = ResolverTester::new("include_empty", None);
+   tt.token_test_run();
+}
+
+
+
+// This tests path on the next line after "@include(". Newline is found where
+// path is expected, thus batch is returned as-is with InstructionError, that
+// points to the Newline.
+// cargo test -F dbg_include_resolver_verbose -F dbg_tokenbuf_verbose -F dbg_tokenizer_verbose --test include_resolver_test resolver_include_fail_test_02 -- --nocapture
+#[test]
+fn resolver_include_fail_test_02() {
+   let mut tt = ResolverTester::new("include_newline_path", None);
+   tt.token_test_run();
+}
@@ -15,7 +15,7 @@ use snailplate_parser::{
 
 
 struct TokenizerTester {
-   tokenizer: Tokenizer,
+   tokenizer: Tokenizer<'static>,
    expected: HashMap<String, Vec<Token>>,
    name_expected: Option<String>,
 }